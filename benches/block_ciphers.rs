@@ -0,0 +1,101 @@
+/*
+* Block cipher throughput benchmark
+*
+* Encrypts 1 MiB through ECB and CBC with DES, 3DES and MiniFeistel64
+* and compares it with expanding the key schedule again for every block,
+* which is what the ciphers used to do before caching it at new/change_key,
+* DES and 3DES are measured with both the reference and the table backend,
+* and the bitsliced DES in the modes that encrypt blocks in batches (ECB, CTR),
+* then the parallelizable modes are run on every available core
+*
+* Run with: cargo bench --bench block_ciphers
+*/
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use cryptography_playground::prelude::*;
+use cryptography_playground::ciphers::des::DESBackend;
+
+const DATA_SIZE: usize = 1 << 20;
+const ITERATIONS: u32 = 5;
+
+const KEY: u64 = 0x133457799BBCDFF1;
+
+// runs f ITERATIONS times and returns the best time
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let throughput = DATA_SIZE as f64 / elapsed.as_secs_f64() / (1 << 20) as f64;
+    println!("{:<48} {:>10.2?} {:>10.2} MiB/s", name, elapsed, throughput);
+}
+
+// encrypts data block by block, building a new cipher (and key schedule) each time
+fn rekeyed_per_block<C: BlockCipher<Block = u64>>(new: impl Fn(u64) -> C, data: &[u8]) -> Vec<u8> {
+    data.chunks(8)
+        .flat_map(|chunk| new(KEY).encrypt_block(u64::from_bytes(chunk).unwrap()).to_bytes())
+        .collect()
+}
+
+fn bench_cipher<C: BlockCipher<Block = u64>>(name: &str, new: impl Fn(u64) -> C, data: &[u8]) {
+    let cipher = new(KEY);
+    let cbc = CBC::<C> { iv: 0x0123456789ABCDEF };
+
+    report(&format!("{} ECB encrypt", name), measure(|| {
+        black_box(ECB.encrypt(&cipher, black_box(data)).unwrap());
+    }));
+    report(&format!("{} CBC encrypt", name), measure(|| {
+        black_box(cbc.encrypt(&cipher, black_box(data)).unwrap());
+    }));
+    report(&format!("{} CBC decrypt", name), measure(|| {
+        black_box(cbc.decrypt(&cipher, black_box(data)).unwrap());
+    }));
+    report(&format!("{} key schedule per block", name), measure(|| {
+        black_box(rekeyed_per_block(&new, black_box(data)));
+    }));
+}
+
+fn main() {
+    let data: Vec<u8> = (0..DATA_SIZE).map(|i| i as u8).collect();
+
+    for backend in [DESBackend::Reference, DESBackend::Table] {
+        bench_cipher(&format!("DES ({:?})", backend), |k| DES::with_backend(k, backend), &data);
+        bench_cipher(&format!("3DES ({:?})", backend), |k| TripleDES::with_backend(k, !k, k.rotate_left(8), backend), &data);
+    }
+    bench_cipher("MiniFeistel64", MiniFeistel64::new, &data);
+
+    // the bitsliced DES only pays off in batches, so only the modes that encrypt in batches (ECB, CTR)
+    let cipher = BitslicedDES::new(KEY);
+    let ctr = CTR::<BitslicedDES>::new(&[0xAB, 0xCD, 0xEF, 0x01], 32).unwrap();
+    report("DES (Bitsliced) ECB encrypt", measure(|| {
+        black_box(ECB.encrypt(&cipher, black_box(&data)).unwrap());
+    }));
+    report("DES (Bitsliced) CTR encrypt", measure(|| {
+        black_box(ctr.encrypt(&cipher, black_box(&data)).unwrap());
+    }));
+
+    // the same parallelizable modes on every available core
+    let cipher = DES::with_backend(KEY, DESBackend::Table);
+    let ecb = Parallel::new(ECB);
+    let cbc = Parallel::new(CBC::<DES> { iv: 0x0123456789ABCDEF });
+    let ctr = Parallel::new(CTR::<DES>::new(&[0xAB, 0xCD, 0xEF, 0x01], 32).unwrap());
+    let workers = ecb.workers();
+    report(&format!("DES (Table) parallel ECB encrypt x{}", workers), measure(|| {
+        black_box(ecb.encrypt(&cipher, black_box(&data)).unwrap());
+    }));
+    report(&format!("DES (Table) parallel CBC decrypt x{}", workers), measure(|| {
+        black_box(cbc.decrypt(&cipher, black_box(&data)).unwrap());
+    }));
+    report(&format!("DES (Table) parallel CTR encrypt x{}", workers), measure(|| {
+        black_box(ctr.encrypt(&cipher, black_box(&data)).unwrap());
+    }));
+}
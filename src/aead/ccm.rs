@@ -0,0 +1,205 @@
+/*
+* Counter with CBC-MAC (NIST SP 800-38C, RFC 3610)
+*
+* MAC-then-encrypt with a single key and a 128 bit block cipher:
+* - T = MSB_Tlen(CBC-MAC(B0 || encoded A || P)), every segment zero padded
+* - C = P XOR CTR(A1, A2, ...) and U = T XOR MSB_Tlen(E(A0))
+* B0 = flags || N || [len(P)]q and A_i = flags' || N || [i]q
+*
+* the q bytes length field and the (15 - q) bytes nonce share the block:
+* a longer nonce means shorter messages (es: q = 2 limits P to 64 KiB)
+*/
+
+use crate::{BlockType, BlockCipher, Error, Result};
+use crate::aead::AEAD;
+use crate::mac::load_block;
+use crate::utils::ct_eq;
+
+pub struct CCM<'a, C: BlockCipher> {
+    cipher: &'a C,
+    // length of the tag in bytes (Tlen / 8)
+    tag_len: usize,
+    // size in bytes of the length field (q)
+    length_size: usize,
+}
+
+impl<'a, C: BlockCipher> CCM<'a, C> {
+    // tag_len in {4, 6, ..., 16}, length_size in [2, 8] (the nonce is 15 - length_size bytes)
+    pub fn new(cipher: &'a C, tag_len: usize, length_size: usize) -> Result<Self> {
+        if C::Block::SIZE != 16 {
+            return Err(Error::InvalidParameter("CCM needs a 128 bit block cipher"));
+        }
+        if !(4..=16).contains(&tag_len) || !tag_len.is_multiple_of(2) {
+            return Err(Error::InvalidParameter("CCM tag length must be 4, 6, ..., 16 bytes"));
+        }
+        if !(2..=8).contains(&length_size) {
+            return Err(Error::InvalidParameter("CCM length field must be 2 to 8 bytes"));
+        }
+        Ok(Self { cipher, tag_len, length_size })
+    }
+
+    // length of the nonce in bytes
+    pub fn nonce_len(&self) -> usize {
+        15 - self.length_size
+    }
+
+    fn check_params(&self, nonce: &[u8], payload_len: usize) -> Result<()> {
+        if nonce.len() != self.nonce_len() {
+            return Err(Error::InvalidParameter("CCM nonce must be 15 - length_size bytes"));
+        }
+        if self.length_size < 8 && payload_len as u64 >= 1u64 << (8 * self.length_size) {
+            return Err(Error::MessageTooLong);
+        }
+        Ok(())
+    }
+
+    // T = MSB_Tlen(CBC-MAC(B0 || encoded A || P))
+    fn mac(&self, nonce: &[u8], aad: &[u8], payload: &[u8]) -> Vec<u8> {
+        // 1. B0 = flags || N || [len(P)]q
+        let flags = (u8::from(!aad.is_empty()) << 6)
+            | (((self.tag_len - 2) / 2) as u8) << 3
+            | (self.length_size - 1) as u8;
+        let mut b0 = vec![flags];
+        b0.extend_from_slice(nonce);
+        b0.extend_from_slice(&(payload.len() as u64).to_be_bytes()[8 - self.length_size..]);
+
+        // 2. associated data prefixed with its encoded length
+        let mut encoded_aad = Vec::new();
+        if !aad.is_empty() {
+            let len = aad.len() as u64;
+            if len < (1 << 16) - (1 << 8) {
+                encoded_aad.extend_from_slice(&(len as u16).to_be_bytes());
+            } else if len < 1 << 32 {
+                encoded_aad.extend_from_slice(&[0xff, 0xfe]);
+                encoded_aad.extend_from_slice(&(len as u32).to_be_bytes());
+            } else {
+                encoded_aad.extend_from_slice(&[0xff, 0xff]);
+                encoded_aad.extend_from_slice(&len.to_be_bytes());
+            }
+            encoded_aad.extend_from_slice(aad);
+        }
+
+        // 3. CBC-MAC over the three segments, each one zero padded
+        let mut y: C::Block = self.cipher.encrypt_block(load_block(&b0));
+        for segment in [&encoded_aad[..], payload] {
+            for chunk in segment.chunks(16) {
+                let mut block = chunk.to_vec();
+                block.resize(16, 0);
+                y = self.cipher.encrypt_block(y ^ load_block(&block));
+            }
+        }
+
+        let mut tag = y.to_bytes();
+        tag.truncate(self.tag_len);
+        tag
+    }
+
+    // counter block A_i = flags' || N || [i]q
+    fn counter_block(&self, nonce: &[u8], i: u64) -> C::Block {
+        let mut block = vec![(self.length_size - 1) as u8];
+        block.extend_from_slice(nonce);
+        block.extend_from_slice(&i.to_be_bytes()[8 - self.length_size..]);
+        load_block(&block)
+    }
+
+    // XORs input with E(A_1), E(A_2), ...
+    fn ctr(&self, nonce: &[u8], input: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(input.len());
+        for (i, chunk) in input.chunks(16).enumerate() {
+            let keystream = self.cipher.encrypt_block(self.counter_block(nonce, i as u64 + 1)).to_bytes();
+            out.extend(chunk.iter().zip(keystream).map(|(b, k)| b ^ k));
+        }
+        out
+    }
+
+    // U = T XOR MSB_Tlen(E(A_0)), the same operation encrypts and decrypts the tag
+    fn mask_tag(&self, nonce: &[u8], tag: &[u8]) -> Vec<u8> {
+        let s0 = self.cipher.encrypt_block(self.counter_block(nonce, 0)).to_bytes();
+        tag.iter().zip(s0).map(|(t, s)| t ^ s).collect()
+    }
+}
+
+impl<C: BlockCipher> AEAD for CCM<'_, C> {
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.check_params(nonce, plaintext.len())?;
+
+        let tag = self.mac(nonce, aad, plaintext);
+        Ok((self.ctr(nonce, plaintext), self.mask_tag(nonce, &tag)))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        self.check_params(nonce, ciphertext.len())?;
+
+        // the MAC is over the plaintext, which is only released if the tag matches
+        let plaintext = self.ctr(nonce, ciphertext);
+        let expected = self.mask_tag(nonce, &self.mac(nonce, aad, &plaintext));
+        if !ct_eq(&expected, tag) {
+            return Err(Error::InvalidTag);
+        }
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+// checks one of the NIST SP 800-38C appendix C examples (key 40 41 ... 4f)
+fn check_sp800_38c_example(tag_len: usize, nonce: &[u8], aad: &[u8], plaintext: &[u8], expected: &str) {
+    use crate::AES;
+    use crate::utils::{hex_decode, hex_encode};
+
+    let cipher = AES::new(&(0x40..0x50).collect::<Vec<u8>>()).unwrap();
+    let ccm = CCM::new(&cipher, tag_len, 15 - nonce.len()).unwrap();
+    let expected = hex_decode(expected).unwrap();
+
+    let (ciphertext, tag) = ccm.seal(nonce, aad, plaintext).unwrap();
+    assert_eq!(hex_encode(&[ciphertext.clone(), tag.clone()].concat()), hex_encode(&expected));
+    assert_eq!(ccm.open(nonce, aad, &ciphertext, &tag).unwrap(), plaintext);
+}
+
+#[test]
+// examples 1-3: growing nonce, associated data, payload and tag
+fn test_ccm_sp800_38c_examples() {
+    let range = |from: u8, to: u8| (from..to).collect::<Vec<u8>>();
+
+    check_sp800_38c_example(4, &range(0x10, 0x17), &range(0, 8), &range(0x20, 0x24), "7162015b4dac255d");
+    check_sp800_38c_example(6, &range(0x10, 0x18), &range(0, 16), &range(0x20, 0x30), "
+        d2a1f0e051ea5f62081a7792073d593d 1fc64fbfaccd
+    ");
+    check_sp800_38c_example(8, &range(0x10, 0x1c), &range(0, 20), &range(0x20, 0x38), "
+        e3b201a9f5b71a7a9b1ceaeccd97e70b 6176aad9a4428aa5 484392fbc1b09951
+    ");
+}
+
+#[test]
+// example 4: 65536 bytes of associated data, encoded with the 0xff 0xfe prefix
+fn test_ccm_sp800_38c_long_aad() {
+    let aad: Vec<u8> = (0..65536).map(|i| i as u8).collect();
+    let nonce: Vec<u8> = (0x10..0x1d).collect();
+    let plaintext: Vec<u8> = (0x20..0x40).collect();
+    check_sp800_38c_example(14, &nonce, &aad, &plaintext, "
+        69915dad1e84c6376a68c2967e4dab615ae0fd1faec44cc484828529463ccf72
+        b4ac6bec93e8598e7f0dadbcea5b
+    ");
+}
+
+#[test]
+fn test_ccm_errors() {
+    use crate::{AES, DES};
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    assert!(matches!(CCM::new(&cipher, 5, 2), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CCM::new(&cipher, 18, 2), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CCM::new(&cipher, 8, 1), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CCM::new(&DES::new(0x133457799BBCDFF1), 8, 2), Err(Error::InvalidParameter(_))));
+
+    let ccm = CCM::new(&cipher, 16, 2).unwrap();
+    assert_eq!(ccm.nonce_len(), 13);
+    assert!(matches!(ccm.seal(&[0; 12], b"", b""), Err(Error::InvalidParameter(_))));
+    assert_eq!(ccm.seal(&[0; 13], b"", &vec![0; 1 << 16]), Err(Error::MessageTooLong));
+
+    let (ciphertext, tag) = ccm.seal(&[0; 13], b"header", b"attack at dawn").unwrap();
+    let mut flipped = ciphertext.clone();
+    flipped[3] ^= 0x01;
+    assert_eq!(ccm.open(&[0; 13], b"header", &flipped, &tag), Err(Error::InvalidTag));
+    assert_eq!(ccm.open(&[0; 13], b"", &ciphertext, &tag), Err(Error::InvalidTag));
+    assert_eq!(ccm.open(&[1; 13], b"header", &ciphertext, &tag), Err(Error::InvalidTag));
+}
@@ -0,0 +1,99 @@
+/*
+* ChaCha20-Poly1305 AEAD as defined by RFC 8439
+*
+* - the one-time Poly1305 key is the first 32 bytes of ChaCha20 block 0
+* - the plaintext is encrypted with ChaCha20 starting from block 1
+* - tag = Poly1305(aad || pad16 || ciphertext || pad16 || [len(aad)]64 || [len(ciphertext)]64)
+*   with little-endian lengths
+*/
+
+use crate::{Error, Result, StreamCipher};
+use crate::aead::AEAD;
+use crate::ciphers::ChaCha20;
+use crate::mac::{Mac, Poly1305};
+use crate::utils::ct_eq;
+
+pub struct ChaCha20Poly1305 {
+    key: Vec<u8>,
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKeyLength(key.len()));
+        }
+        Ok(Self { key: key.to_vec() })
+    }
+
+    // Poly1305 key generation (RFC 8439 2.6)
+    fn poly1305_key(&self, nonce: &[u8]) -> Result<Vec<u8>> {
+        let block = ChaCha20::new(&self.key, nonce, 0)?.block(0);
+        Ok(block[..32].to_vec())
+    }
+
+    fn tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let mut poly = Poly1305::new(&self.poly1305_key(nonce)?)?;
+        for data in [aad, ciphertext] {
+            poly.update(data);
+            poly.update(&[0u8; 16][..(16 - data.len() % 16) % 16]);
+        }
+        poly.update(&(aad.len() as u64).to_le_bytes());
+        poly.update(&(ciphertext.len() as u64).to_le_bytes());
+        Ok(poly.finalize())
+    }
+}
+
+impl AEAD for ChaCha20Poly1305 {
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut ciphertext = plaintext.to_vec();
+        ChaCha20::new(&self.key, nonce, 1)?.apply_keystream(&mut ciphertext)?;
+        let tag = self.tag(nonce, aad, &ciphertext)?;
+        Ok((ciphertext, tag))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        if !ct_eq(&self.tag(nonce, aad, ciphertext)?, tag) {
+            return Err(Error::InvalidTag);
+        }
+        let mut plaintext = ciphertext.to_vec();
+        ChaCha20::new(&self.key, nonce, 1)?.apply_keystream(&mut plaintext)?;
+        Ok(plaintext)
+    }
+}
+
+#[test]
+// RFC 8439 2.6.2 Poly1305 key generation test vector
+fn test_chacha20_poly1305_key_generation() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let aead = ChaCha20Poly1305::new(&(0x80..0xa0).collect::<Vec<u8>>()).unwrap();
+    let key = aead.poly1305_key(&hex_decode("000000000001020304050607").unwrap()).unwrap();
+    assert_eq!(hex_encode(&key), "8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646");
+}
+
+#[test]
+// RFC 8439 2.8.2 AEAD test vector
+fn test_chacha20_poly1305_rfc8439() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let aead = ChaCha20Poly1305::new(&(0x80..0xa0).collect::<Vec<u8>>()).unwrap();
+    let nonce = hex_decode("070000004041424344454647").unwrap();
+    let aad = hex_decode("50515253c0c1c2c3c4c5c6c7").unwrap();
+    let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    let (ciphertext, tag) = aead.seal(&nonce, &aad, plaintext).unwrap();
+    assert_eq!(hex_encode(&ciphertext), "\
+        d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+        3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+        92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+        3ff4def08e4b7a9de576d26586cec64b6116");
+    assert_eq!(hex_encode(&tag), "1ae10b594f09e26a7e902ecbd0600691");
+    assert_eq!(aead.open(&nonce, &aad, &ciphertext, &tag).unwrap(), plaintext);
+
+    let mut flipped = ciphertext.clone();
+    flipped[0] ^= 0x01;
+    assert_eq!(aead.open(&nonce, &aad, &flipped, &tag), Err(Error::InvalidTag));
+    assert_eq!(aead.open(&nonce, b"", &ciphertext, &tag), Err(Error::InvalidTag));
+    assert!(matches!(aead.seal(&[0; 8], &aad, plaintext), Err(Error::InvalidParameter(_))));
+    assert_eq!(ChaCha20Poly1305::new(&[0; 16]).err(), Some(Error::InvalidKeyLength(16)));
+}
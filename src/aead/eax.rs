@@ -0,0 +1,165 @@
+/*
+* EAX mode (Bellare, Rogaway & Wagner, 2004)
+*
+* encrypt-then-MAC with a single key, built from CTR and OMAC (CMAC):
+*   N' = OMAC_0(N)   H' = OMAC_1(H)   C = CTR_N'(M)   C' = OMAC_2(C)
+*   T = MSB_tag_len(N' XOR C' XOR H')
+* where OMAC_t(X) = CMAC([t]_n || X) and [t]_n is t as a full block.
+* Nonce and header can have any length and any block size works (es: 64 bit DES)
+*/
+
+use crate::{BlockType, BlockCipher, Error, Result};
+use crate::aead::AEAD;
+use crate::mac::{CMAC, Mac, load_block};
+use crate::utils::ct_eq;
+
+pub struct EAX<'a, C: BlockCipher> {
+    cipher: &'a C,
+    // length of the tag in bytes
+    tag_len: usize,
+}
+
+impl<'a, C: BlockCipher> EAX<'a, C> {
+    // EAX with a full block tag
+    pub fn new(cipher: &'a C) -> Self {
+        Self { cipher, tag_len: C::Block::SIZE }
+    }
+
+    // EAX with a truncated tag of 1 to block size bytes
+    pub fn with_tag_len(cipher: &'a C, tag_len: usize) -> Result<Self> {
+        if tag_len == 0 || tag_len > C::Block::SIZE {
+            return Err(Error::InvalidParameter("EAX tag length must be in [1, block size] bytes"));
+        }
+        Ok(Self { cipher, tag_len })
+    }
+
+    // OMAC_t(data) = CMAC([t]_n || data)
+    fn omac(&self, t: u8, data: &[u8]) -> Vec<u8> {
+        let mut prefix = vec![0u8; C::Block::SIZE];
+        prefix[C::Block::SIZE - 1] = t;
+        let mut cmac = CMAC::new(self.cipher);
+        cmac.update(&prefix);
+        cmac.update(data);
+        cmac.finalize()
+    }
+
+    // CTR mode starting from N', the whole block is the counter
+    fn ctr(&self, initial: &[u8], input: &[u8]) -> Vec<u8> {
+        let mut counter = initial.to_vec();
+        let mut out = Vec::with_capacity(input.len());
+        for chunk in input.chunks(C::Block::SIZE) {
+            let keystream = self.cipher.encrypt_block(load_block(&counter)).to_bytes();
+            out.extend(chunk.iter().zip(keystream).map(|(b, k)| b ^ k));
+
+            // big-endian increment modulo 2^n
+            for byte in counter.iter_mut().rev() {
+                *byte = byte.wrapping_add(1);
+                if *byte != 0 {
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    // T = MSB(N' XOR C' XOR H')
+    fn tag(&self, n: &[u8], aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+        let h = self.omac(1, aad);
+        let c = self.omac(2, ciphertext);
+        n.iter().zip(h).zip(c).map(|((n, h), c)| n ^ h ^ c).take(self.tag_len).collect()
+    }
+}
+
+impl<C: BlockCipher> AEAD for EAX<'_, C> {
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let n = self.omac(0, nonce);
+        let ciphertext = self.ctr(&n, plaintext);
+        let tag = self.tag(&n, aad, &ciphertext);
+        Ok((ciphertext, tag))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        let n = self.omac(0, nonce);
+        if !ct_eq(&self.tag(&n, aad, ciphertext), tag) {
+            return Err(Error::InvalidTag);
+        }
+        Ok(self.ctr(&n, ciphertext))
+    }
+}
+
+#[test]
+// test vectors from the EAX paper (AES-128)
+fn test_eax_aes_known_vectors() {
+    use crate::AES;
+    use crate::utils::{hex_decode, hex_encode};
+
+    // (msg, key, nonce, header, cipher || tag)
+    let vectors = [
+        (
+            "",
+            "233952DEE4D5ED5F9B9C6D6FF80FF478",
+            "62EC67F9C3A4A407FCB2A8C49031A8B3",
+            "6BFB914FD07EAE6B",
+            "E037830E8389F27B025A2D6527E79D01",
+        ),
+        (
+            "F7FB",
+            "91945D3F4DCBEE0BF45EF52255F095A4",
+            "BECAF043B0A23D843194BA972C66DEBD",
+            "FA3BFD4806EB53FA",
+            "19DD5C4C9331049D0BDAB0277408F67967E5",
+        ),
+        (
+            "1A47CB4933",
+            "01F74AD64077F2E704C0F60ADA3DD523",
+            "70C3DB4F0D26368400A10ED05D2BFF5E",
+            "234A3463C1264AC6",
+            "D851D5BAE03A59F238A23E39199DC9266626C40F80",
+        ),
+    ];
+    for (msg, key, nonce, header, expected) in vectors {
+        let cipher = AES::new(&hex_decode(key).unwrap()).unwrap();
+        let eax = EAX::new(&cipher);
+        let (msg, nonce, header) = (hex_decode(msg).unwrap(), hex_decode(nonce).unwrap(), hex_decode(header).unwrap());
+
+        let (ciphertext, tag) = eax.seal(&nonce, &header, &msg).unwrap();
+        assert_eq!(hex_encode(&[ciphertext.clone(), tag.clone()].concat()), expected.to_lowercase());
+        assert_eq!(eax.open(&nonce, &header, &ciphertext, &tag).unwrap(), msg);
+    }
+}
+
+#[test]
+// 64 bit block ciphers work too
+fn test_eax_64bit_blocks() {
+    use crate::{DES, MiniFeistel64};
+
+    fn check<C: BlockCipher>(cipher: &C) {
+        let eax = EAX::new(cipher);
+        let message = b"attack at dawn, bring snacks";
+        let (ciphertext, tag) = eax.seal(b"nonce", b"header", message).unwrap();
+        assert_eq!(tag.len(), 8);
+        assert_eq!(eax.open(b"nonce", b"header", &ciphertext, &tag).unwrap(), message);
+
+        let mut flipped = ciphertext.clone();
+        flipped[20] ^= 0x01;
+        assert_eq!(eax.open(b"nonce", b"header", &flipped, &tag), Err(Error::InvalidTag));
+        assert_eq!(eax.open(b"nonce", b"Header", &ciphertext, &tag), Err(Error::InvalidTag));
+        assert_eq!(eax.open(b"Nonce", b"header", &ciphertext, &tag), Err(Error::InvalidTag));
+    }
+
+    check(&DES::new(0x133457799BBCDFF1));
+    check(&MiniFeistel64::new(0x133457799BBCDFF1));
+}
+
+#[test]
+fn test_eax_truncated_tag() {
+    use crate::AES;
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let (_, full) = EAX::new(&cipher).seal(b"n", b"h", b"m").unwrap();
+    let eax = EAX::with_tag_len(&cipher, 8).unwrap();
+    let (ciphertext, tag) = eax.seal(b"n", b"h", b"m").unwrap();
+    assert_eq!(tag, full[..8]);
+    assert_eq!(eax.open(b"n", b"h", &ciphertext, &tag).unwrap(), b"m");
+    assert!(matches!(EAX::with_tag_len(&cipher, 17), Err(Error::InvalidParameter(_))));
+}
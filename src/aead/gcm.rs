@@ -0,0 +1,263 @@
+/*
+* Galois/Counter Mode (McGrew & Viega, NIST SP 800-38D)
+*
+* - confidentiality: CTR mode with a 32 bit counter starting from inc32(J0)
+* - authenticity: GHASH, a polynomial MAC over GF(2^128) keyed with H = E(0^128)
+*   tag = E(J0) XOR GHASH_H(A || 0* || C || 0* || [len(A)]64 || [len(C)]64)
+*
+* J0 = IV || 0^31 || 1 for 96 bit IVs, GHASH_H(IV || 0* || [len(IV)]64) otherwise
+*
+* NOTE: reusing a nonce with the same key leaks H and allows forgeries
+*/
+
+use crate::{BlockCipher, Error, Result};
+use crate::aead::AEAD;
+use crate::utils::ct_eq;
+
+// reduction polynomial x^128 + x^7 + x^2 + x + 1 (bit reflected)
+const R: u128 = 0xE1 << 120;
+
+pub struct GCM<'a, C: BlockCipher<Block = u128>> {
+    cipher: &'a C,
+    // hash subkey H = E(0^128)
+    h: u128,
+    // length of the tag in bytes
+    tag_len: usize,
+}
+
+impl<'a, C: BlockCipher<Block = u128>> GCM<'a, C> {
+    // GCM with a full 128 bit tag
+    pub fn new(cipher: &'a C) -> Self {
+        Self { cipher, h: cipher.encrypt_block(0), tag_len: 16 }
+    }
+
+    // GCM with a truncated tag, SP 800-38D allows 4, 8 and 12 to 16 bytes
+    pub fn with_tag_len(cipher: &'a C, tag_len: usize) -> Result<Self> {
+        if !matches!(tag_len, 4 | 8 | 12..=16) {
+            return Err(Error::InvalidParameter("GCM tag length must be 4, 8 or 12 to 16 bytes"));
+        }
+        Ok(Self { tag_len, ..Self::new(cipher) })
+    }
+
+    // pre-counter block J0
+    fn j0(&self, nonce: &[u8]) -> Result<u128> {
+        if nonce.is_empty() {
+            return Err(Error::InvalidParameter("GCM nonce can't be empty"));
+        }
+        if nonce.len() == 12 {
+            let mut block = [0u8; 16];
+            block[..12].copy_from_slice(nonce);
+            block[15] = 1;
+            return Ok(u128::from_be_bytes(block));
+        }
+        let mut y = ghash_update(self.h, 0, nonce);
+        y = gf_mul(y ^ (nonce.len() as u128 * 8), self.h);
+        Ok(y)
+    }
+
+    // GCTR: CTR mode with a 32 bit counter starting from icb
+    fn gctr(&self, icb: u128, input: &[u8]) -> Vec<u8> {
+        let mut counter = icb;
+        let mut out = Vec::with_capacity(input.len());
+        for chunk in input.chunks(16) {
+            let keystream = self.cipher.encrypt_block(counter).to_be_bytes();
+            out.extend(chunk.iter().zip(keystream).map(|(b, k)| b ^ k));
+            counter = inc32(counter);
+        }
+        out
+    }
+
+    // full (untruncated) tag over aad and ciphertext
+    fn tag(&self, j0: u128, aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+        let mut s = ghash_update(self.h, 0, aad);
+        s = ghash_update(self.h, s, ciphertext);
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        s = gf_mul(s ^ lengths, self.h);
+        let mut tag = (self.cipher.encrypt_block(j0) ^ s).to_be_bytes().to_vec();
+        tag.truncate(self.tag_len);
+        tag
+    }
+}
+
+impl<C: BlockCipher<Block = u128>> AEAD for GCM<'_, C> {
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        check_text_len(plaintext.len())?;
+        let j0 = self.j0(nonce)?;
+        let ciphertext = self.gctr(inc32(j0), plaintext);
+        let tag = self.tag(j0, aad, &ciphertext);
+        Ok((ciphertext, tag))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        check_text_len(ciphertext.len())?;
+        let j0 = self.j0(nonce)?;
+        if !ct_eq(&self.tag(j0, aad, ciphertext), tag) {
+            return Err(Error::InvalidTag);
+        }
+        Ok(self.gctr(inc32(j0), ciphertext))
+    }
+}
+
+// multiplication in GF(2^128) (SP 800-38D algorithm 1)
+// the bits of a block are the coefficients of x^0 ... x^127, from the leftmost one
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        if (x >> (127 - i)) & 1 == 1 {
+            z ^= v;
+        }
+        v = if v & 1 == 1 { (v >> 1) ^ R } else { v >> 1 };
+    }
+    z
+}
+
+// absorbs data (zero padded to a whole number of blocks) into the GHASH state y
+fn ghash_update(h: u128, mut y: u128, data: &[u8]) -> u128 {
+    for chunk in data.chunks(16) {
+        let mut block = [0u8; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        y = gf_mul(y ^ u128::from_be_bytes(block), h);
+    }
+    y
+}
+
+// the 32 bit counter limits plaintexts and ciphertexts to 2^32 - 2 blocks
+fn check_text_len(len: usize) -> Result<()> {
+    if len as u64 > ((1u64 << 32) - 2) * 16 {
+        return Err(Error::MessageTooLong);
+    }
+    Ok(())
+}
+
+// increments the rightmost 32 bits modulo 2^32
+fn inc32(block: u128) -> u128 {
+    let counter = (block as u32).wrapping_add(1);
+    (block & !(u32::MAX as u128)) | counter as u128
+}
+
+#[cfg(test)]
+// checks one of the test cases of the original GCM specification (McGrew & Viega, appendix B)
+fn check_gcm_spec_vector(key: &str, iv: &str, aad: &str, plaintext: &str, ciphertext: &str, tag: &str) {
+    use crate::AES;
+    use crate::utils::{hex_decode, hex_encode};
+
+    let cipher = AES::new(&hex_decode(key).unwrap()).unwrap();
+    let gcm = GCM::new(&cipher);
+    let (iv, aad, plaintext) = (hex_decode(iv).unwrap(), hex_decode(aad).unwrap(), hex_decode(plaintext).unwrap());
+
+    let (c, t) = gcm.seal(&iv, &aad, &plaintext).unwrap();
+    assert_eq!(hex_encode(&c), ciphertext.split_whitespace().collect::<String>());
+    assert_eq!(hex_encode(&t), tag);
+    assert_eq!(gcm.open(&iv, &aad, &c, &t).unwrap(), plaintext);
+}
+
+#[cfg(test)]
+const SPEC_KEY: &str = "feffe9928665731c6d6a8f9467308308";
+#[cfg(test)]
+const SPEC_PLAINTEXT: &str = "
+    d9313225f88406e5a55909c5aff5269a 86a7a9531534f7da2e4c303d8a318a72
+    1c3c0c95956809532fcf0e2449a6b525 b16aedf5aa0de657ba637b391aafd255
+";
+#[cfg(test)]
+const SPEC_AAD: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+#[cfg(test)]
+// the 60 bytes plaintext used together with associated data
+fn truncated_spec_plaintext() -> String {
+    SPEC_PLAINTEXT.split_whitespace().collect::<String>()[..120].to_string()
+}
+
+#[test]
+// test cases 1 and 2: all zero key, IV and plaintext
+fn test_gcm_spec_zero_vectors() {
+    let zero_key = "00000000000000000000000000000000";
+    let zero_iv = "000000000000000000000000";
+    check_gcm_spec_vector(zero_key, zero_iv, "", "", "", "58e2fccefa7e3061367f1d57a4e7455a");
+    check_gcm_spec_vector(
+        zero_key,
+        zero_iv,
+        "",
+        "00000000000000000000000000000000",
+        "0388dace60b6a392f328c2b971b2fe78",
+        "ab6e47d42cec13bdf53a67b21257bddf",
+    );
+}
+
+#[test]
+// test cases 3 and 4: 96 bit IV, without and with associated data
+fn test_gcm_spec_96bit_iv_vectors() {
+    check_gcm_spec_vector(SPEC_KEY, "cafebabefacedbaddecaf888", "", SPEC_PLAINTEXT, "
+        42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+        21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091473f5985
+    ", "4d5c2af327cd64a62cf35abd2ba6fab4");
+
+    let plaintext = truncated_spec_plaintext();
+    check_gcm_spec_vector(SPEC_KEY, "cafebabefacedbaddecaf888", SPEC_AAD, &plaintext, "
+        42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+        21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091
+    ", "5bc94fbc3221a5db94fae95ae7121a47");
+}
+
+#[test]
+// test cases 5 and 6: 64 bit and 480 bit IVs, J0 is derived with GHASH
+fn test_gcm_spec_other_iv_lengths() {
+    let plaintext = truncated_spec_plaintext();
+    check_gcm_spec_vector(SPEC_KEY, "cafebabefacedbad", SPEC_AAD, &plaintext, "
+        61353b4c2806934a777ff51fa22a4755 699b2a714fcdc6f83766e5f97b6c7423
+        73806900e49f24b22b097544d4896b42 4989b5e1ebac0f07c23f4598
+    ", "3612d2e79e3b0785561be14aaca2fccb");
+
+    let iv = "
+        9313225df88406e555909c5aff5269aa 6a7a9538534f7da1e4c303d2a318a728
+        c3c0c95156809539fcf0e2429a6b5254 16aedbf5a0de6a57a637b39b
+    ";
+    check_gcm_spec_vector(SPEC_KEY, iv, SPEC_AAD, &plaintext, "
+        8ce24998625615b603a033aca13fb894 be9112a5c3a211a8ba262a3cca7e2ca7
+        01e4a9a4fba43c90ccdcb281d48c7c6f d62875d2aca417034c34aee5
+    ", "619cc5aefffe0bfa462af43c1699d050");
+}
+
+#[test]
+// test case 16: AES-256
+fn test_gcm_spec_aes256_vector() {
+    let plaintext = truncated_spec_plaintext();
+    let key = format!("{}{}", SPEC_KEY, SPEC_KEY);
+    check_gcm_spec_vector(&key, "cafebabefacedbaddecaf888", SPEC_AAD, &plaintext, "
+        522dc1f099567d07f47f37a32a84427d 643a8cdcbfe5c0c97598a2bd2555d1aa
+        8cb08e48590dbb3da7b08b1056828838 c5f61e6393ba7a0abcc9f662
+    ", "76fc6ece0f4e1768cddf8853bb2d551b");
+}
+
+#[test]
+fn test_gcm_rejects_tampering() {
+    use crate::AES;
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let gcm = GCM::new(&cipher);
+    let nonce = [0x01; 12];
+    let (ciphertext, tag) = gcm.seal(&nonce, b"header", b"attack at dawn").unwrap();
+
+    let mut flipped = ciphertext.clone();
+    flipped[0] ^= 0x01;
+    assert_eq!(gcm.open(&nonce, b"header", &flipped, &tag), Err(Error::InvalidTag));
+    assert_eq!(gcm.open(&nonce, b"Header", &ciphertext, &tag), Err(Error::InvalidTag));
+    assert_eq!(gcm.open(&[0x02; 12], b"header", &ciphertext, &tag), Err(Error::InvalidTag));
+    assert_eq!(gcm.open(&nonce, b"header", &ciphertext, &tag[..12]), Err(Error::InvalidTag));
+    assert!(matches!(gcm.seal(&[], b"", b""), Err(Error::InvalidParameter(_))));
+
+    // truncated tags are prefixes of the full one
+    let short = GCM::with_tag_len(&cipher, 12).unwrap();
+    let (_, short_tag) = short.seal(&nonce, b"header", b"attack at dawn").unwrap();
+    assert_eq!(short_tag, tag[..12]);
+    assert_eq!(short.open(&nonce, b"header", &ciphertext, &short_tag).unwrap(), b"attack at dawn");
+    assert!(matches!(GCM::with_tag_len(&cipher, 10), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+// seal and open accept the same lengths (longer inputs would wrap the 32 bit counter)
+fn test_gcm_text_len_limit() {
+    let max = ((1usize << 32) - 2) * 16;
+    assert_eq!(check_text_len(max), Ok(()));
+    assert_eq!(check_text_len(max + 1), Err(Error::MessageTooLong));
+}
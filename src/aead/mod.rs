@@ -0,0 +1,23 @@
+pub mod ccm;
+pub mod chacha20_poly1305;
+pub mod eax;
+pub mod gcm;
+
+pub use ccm::CCM;
+pub use chacha20_poly1305::ChaCha20Poly1305;
+pub use eax::EAX;
+pub use gcm::GCM;
+
+use crate::Result;
+
+// general trait for Authenticated Encryption with Associated Data (GCM, ...)
+// the key (or keyed cipher) is given to the constructor of each implementation
+pub trait AEAD {
+    // encrypts plaintext and authenticates it together with aad
+    // returns (ciphertext, tag)
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    // checks tag over aad and ciphertext, then decrypts
+    // nothing is decrypted if the tag doesn't match (Error::InvalidTag)
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>>;
+}
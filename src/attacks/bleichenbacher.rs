@@ -0,0 +1,152 @@
+/*
+* Bleichenbacher's attack against RSAES-PKCS1-v1_5 (CRYPTO '98)
+*
+* Given only the public key (n, e) and a padding oracle telling whether c' decrypts
+* to a block starting with 0x00 || 0x02, recovers m = c^d mod n.
+* RSA is multiplicative: (c * s^e)^d = m * s mod n, so every conforming answer for
+* some s means 2B <= m * s mod n < 3B (B = 2^(8(k-2))), which narrows down the set of
+* intervals containing m until a single value is left.
+*/
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+
+use crate::{Error, Result};
+use crate::ciphers::rsa::pkcs1v15::PaddingOracle;
+use crate::ciphers::rsa::rsa_utils::{i2osp, os2ip, random_below};
+
+// recovers the whole k bytes encoded message hidden in ciphertext
+// (es: decode it with PKCS1v15Encrypt to get the plaintext)
+pub fn recover(oracle: &PaddingOracle, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let (n, e) = oracle.public_key();
+    let k = n.bits().div_ceil(8) as usize;
+    if ciphertext.len() != k || k < 11 {
+        return Err(Error::InvalidParameter("ciphertext must be as long as the modulus"));
+    }
+
+    let c = os2ip(ciphertext);
+    if &c >= n {
+        return Err(Error::MessageOutOfRange);
+    }
+    let b = BigUint::from(1u32) << (8 * (k - 2));
+    let b2 = &b * 2u32;
+    let b3 = &b * 3u32;
+    // step 1: blinding, find s0 so that c * s0^e is conforming (s0 = 1 if c already is)
+    let mut s0 = BigUint::from(1u32);
+    while !oracle.is_conforming(&c0_of(&c, &s0, e, n)) {
+        s0 = random_below(n);
+    }
+    let c0 = c0_of(&c, &s0, e, n);
+    let query = |s: &BigUint| oracle.is_conforming(&c0_of(&c0, s, e, n));
+
+    let mut intervals = vec![(b2.clone(), &b3 - 1u32)];
+    let mut s = n.div_ceil(&b3);
+    let mut first = true;
+
+    loop {
+        if first {
+            // step 2a: smallest s >= n / 3B
+            while !query(&s) {
+                s += 1u32;
+            }
+            first = false;
+        } else if intervals.len() > 1 {
+            // step 2b: several intervals left, keep searching linearly
+            s += 1u32;
+            while !query(&s) {
+                s += 1u32;
+            }
+        } else {
+            // step 2c: a single interval [a, b], search with r roughly doubling s
+            let (lo, hi) = &intervals[0];
+            let mut r = (2u32 * (hi * &s - &b2)).div_ceil(n);
+            'search: loop {
+                let rn = &r * n;
+                let mut candidate = (&b2 + &rn).div_ceil(hi);
+                let upper = (&b3 + &rn).div_ceil(lo);
+                while candidate < upper {
+                    if query(&candidate) {
+                        s = candidate;
+                        break 'search;
+                    }
+                    candidate += 1u32;
+                }
+                r += 1u32;
+            }
+        }
+
+        // step 3: narrow the set of solutions
+        let mut narrowed: Vec<(BigUint, BigUint)> = Vec::new();
+        for (lo, hi) in &intervals {
+            let lo_s = lo * &s + 1u32;
+            let r_min = if lo_s > b3 { (lo_s - &b3).div_ceil(n) } else { BigUint::ZERO };
+            let r_max = (hi * &s - &b2) / n;
+
+            let mut r = r_min;
+            while r <= r_max {
+                let rn = &r * n;
+                let new_lo = (&b2 + &rn).div_ceil(&s).max(lo.clone());
+                let new_hi = ((&b3 - 1u32 + &rn) / &s).min(hi.clone());
+                if new_lo <= new_hi {
+                    insert_interval(&mut narrowed, new_lo, new_hi);
+                }
+                r += 1u32;
+            }
+        }
+        if narrowed.is_empty() {
+            // can only happen with a lying oracle
+            return Err(Error::DecryptionFailed);
+        }
+        intervals = narrowed;
+
+        // step 4: a single value left, unblind it
+        if intervals.len() == 1 && intervals[0].0 == intervals[0].1 {
+            let s0_inv = s0.modinv(n).ok_or(Error::NoModularInverse)?;
+            let m = (&intervals[0].0 * s0_inv) % n;
+            return i2osp(&m, k);
+        }
+    }
+}
+
+// c * s^e mod n
+fn c0_of(c: &BigUint, s: &BigUint, e: &BigUint, n: &BigUint) -> BigUint {
+    (c * s.modpow(e, n)) % n
+}
+
+// adds [lo, hi] to the set, merging it with the intervals it overlaps
+fn insert_interval(intervals: &mut Vec<(BigUint, BigUint)>, mut lo: BigUint, mut hi: BigUint) {
+    intervals.retain(|(a, b)| {
+        let overlaps = *a <= hi && lo <= *b;
+        if overlaps {
+            lo = lo.clone().min(a.clone());
+            hi = hi.clone().max(b.clone());
+        }
+        !overlaps
+    });
+    intervals.push((lo, hi));
+}
+
+#[test]
+fn test_bleichenbacher_recovers_plaintext() {
+    use crate::RSA;
+    use crate::ciphers::rsa::PKCS1v15Encrypt;
+
+    // small key so that the attack runs in a reasonable time
+    let key = RSA::gen_keypair(512).unwrap();
+    let ciphertext = PKCS1v15Encrypt.encrypt(&key, b"attack at dawn").unwrap();
+
+    let oracle = PaddingOracle::new(&key);
+    let em = recover(&oracle, &ciphertext).unwrap();
+    assert_eq!(PKCS1v15Encrypt::decode(&em), Some(&b"attack at dawn"[..]));
+    assert!(oracle.queries() > 0);
+}
+
+#[test]
+fn test_bleichenbacher_errors() {
+    use crate::RSA;
+
+    let key = RSA::gen_keypair(512).unwrap();
+    let oracle = PaddingOracle::new(&key);
+    assert!(matches!(recover(&oracle, &[0x01; 10]), Err(Error::InvalidParameter(_))));
+    assert_eq!(recover(&oracle, &[0xFF; 64]), Err(Error::MessageOutOfRange));
+}
@@ -0,0 +1,58 @@
+/*
+* Forgery against raw CBC-MAC on variable length messages
+*
+* after processing M1 the CBC-MAC chaining value is its tag T1, so for
+*
+*   M = M1 || (M2_1 XOR T1) || M2_2 || ... || M2_n
+*
+* the first block of M2 enters the cipher as (M2_1 XOR T1) XOR T1 = M2_1
+* and MAC(M) = MAC(M2): a valid tag for a message that was never authenticated.
+* CMAC isn't affected because the last block of M1 is masked with a subkey.
+*/
+
+use crate::{Error, Result};
+
+// builds a message whose CBC-MAC is tag2, given the (block aligned) message1 with tag1
+// and message2 with tag2
+pub fn splice(message1: &[u8], tag1: &[u8], message2: &[u8]) -> Result<Vec<u8>> {
+    let block_size = tag1.len();
+    if block_size == 0 || !message1.len().is_multiple_of(block_size) || message2.len() < block_size {
+        return Err(Error::InvalidParameter("messages must be block aligned and not empty"));
+    }
+
+    let mut forged = message1.to_vec();
+    forged.extend(message2[..block_size].iter().zip(tag1).map(|(m, t)| m ^ t));
+    forged.extend_from_slice(&message2[block_size..]);
+    Ok(forged)
+}
+
+#[test]
+fn test_cbc_mac_splice_forgery() {
+    use crate::{AES, DES, BlockCipher};
+    use crate::mac::{CBCMAC, CMAC};
+
+    fn check<C: BlockCipher>(cipher: &C) {
+        let message1 = b"pay 100 euros to alice..........";
+        let message2 = b"pay 999 euros to mallory........";
+        let tag1 = CBCMAC::mac(cipher, message1);
+        let tag2 = CBCMAC::mac(cipher, message2);
+
+        // the attacker never asked for the tag of the forged message
+        let forged = splice(message1, &tag1, message2).unwrap();
+        assert_eq!(CBCMAC::mac(cipher, &forged), tag2);
+
+        // the same trick fails against CMAC
+        let cmac_tag1 = CMAC::mac(cipher, message1);
+        let forged = splice(message1, &cmac_tag1, message2).unwrap();
+        assert_ne!(CMAC::mac(cipher, &forged), CMAC::mac(cipher, message2));
+    }
+
+    check(&DES::new(0x133457799BBCDFF1));
+    check(&AES::new(&[0x2b; 16]).unwrap());
+}
+
+#[test]
+fn test_cbc_mac_splice_errors() {
+    assert!(matches!(splice(b"abc", &[0; 8], &[0; 8]), Err(Error::InvalidParameter(_))));
+    assert!(matches!(splice(&[0; 8], &[0; 8], b"abc"), Err(Error::InvalidParameter(_))));
+}
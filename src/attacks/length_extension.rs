@@ -0,0 +1,72 @@
+/*
+* Length extension attack against secret-prefix MACs built on Merkle-Damgard hashes
+*
+* tag = H(secret || message) is the final chaining value of the hash, so without knowing
+* the secret an attacker can resume from it and compute
+*
+*   H(secret || message || pad || extension)
+*
+* where pad is the padding the hash appended to secret || message.
+* Only the length of the secret is needed (or guessed). HMAC isn't affected.
+*/
+
+use crate::{Error, Result};
+use crate::hash::MerkleDamgardHash;
+
+// forges the tag of message || pad || extension from tag = H(secret || message)
+// returns the forged message and its tag
+pub fn extend<H: MerkleDamgardHash>(
+    tag: &[u8],
+    secret_len: usize,
+    message: &[u8],
+    extension: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    // 1. the padding appended to secret || message by the original computation
+    let original_len = (secret_len + message.len()) as u64;
+    let padding = H::padding(original_len);
+
+    // 2. resume from the tag as if secret || message || pad had been processed
+    let mut hasher = H::from_digest(tag, original_len)
+        .ok_or(Error::InvalidParameter("tag must be a full digest of the hash"))?;
+    hasher.update(extension);
+
+    let forged_message = [message, &padding, extension].concat();
+    Ok((forged_message, hasher.finalize()))
+}
+
+#[cfg(test)]
+// the victim: MACs with H(secret || message) and checks tags
+fn check_forgery<H: MerkleDamgardHash>() {
+    let secret = b"super secret key";
+    let mac = |message: &[u8]| H::digest(&[&secret[..], message].concat());
+
+    let message = b"user=guest&role=user";
+    let tag = mac(message);
+
+    // the attacker only knows message, tag and the length of the secret
+    let (forged_message, forged_tag) = extend::<H>(&tag, secret.len(), message, b"&role=admin").unwrap();
+    assert!(forged_message.starts_with(message));
+    assert!(forged_message.ends_with(b"&role=admin"));
+    assert_eq!(mac(&forged_message), forged_tag);
+
+    // a wrong guess of the secret length gives a wrong tag
+    let (wrong_message, wrong_tag) = extend::<H>(&tag, secret.len() + 1, message, b"&role=admin").unwrap();
+    assert_ne!(mac(&wrong_message), wrong_tag);
+}
+
+#[test]
+fn test_length_extension_md5() {
+    check_forgery::<crate::hash::MD5>();
+}
+
+#[test]
+fn test_length_extension_sha256() {
+    check_forgery::<crate::hash::SHA256>();
+}
+
+#[test]
+fn test_length_extension_bad_tag() {
+    use crate::hash::SHA256;
+
+    assert!(matches!(extend::<SHA256>(&[0u8; 20], 16, b"m", b"x"), Err(Error::InvalidParameter(_))));
+}
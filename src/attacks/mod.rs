@@ -0,0 +1,8 @@
+/*
+* Attacks against the primitives of this crate
+* NOTE: for teaching purposes only, they're here to show why the countermeasures exist
+*/
+
+pub mod bleichenbacher;
+pub mod cbc_mac;
+pub mod length_extension;
@@ -0,0 +1,171 @@
+/*
+* An AES (Rijndael) implementation in pure Rust as defined by FIPS 197
+*
+* Carachteristics:
+* - block size is 128 bit
+* - key size is 128, 192 or 256 bit
+* - 10, 12 or 14 rounds based on the key size
+* - substitution-permutation network (not a feistel one like DES)
+*/
+
+use crate::{BlockCipher, Error, Result};
+use crate::ciphers::aes::aes_constants::*;
+use crate::ciphers::aes::aes_utils::*;
+
+pub struct AES {
+    // round keys derived from the currently used key
+    round_keys: Vec<RoundKey>
+}
+
+impl BlockCipher for AES {
+    type Block = u128;
+
+    fn encrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.aes_encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.aes_decrypt_block(block)
+    }
+}
+
+impl AES {
+    // initialize the cipher with a 16, 24 or 32 bytes key
+    // (AES-128, AES-192 or AES-256)
+    pub fn new(key: &[u8]) -> Result<Self> {
+        Ok(Self { round_keys: expand_key(key, Self::rounds_for_key(key)?) })
+    }
+
+    // change the secret key to use
+    pub fn change_key(&mut self, new_key: &[u8]) -> Result<()> {
+        self.round_keys = expand_key(new_key, Self::rounds_for_key(new_key)?);
+        Ok(())
+    }
+
+    // number of rounds used by the current key
+    pub fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    // encrypts a single block
+    pub fn aes_encrypt_block(&self, p: u128) -> u128 {
+        let rounds = self.rounds();
+        let mut state = block_to_state(p);
+
+        // 1) initial round key addition
+        add_round_key(&mut state, &self.round_keys[0]);
+
+        // 2) Nr - 1 full rounds
+        for round_key in &self.round_keys[1..rounds] {
+            sub_bytes(&mut state, false);
+            shift_rows(&mut state, false);
+            mix_columns(&mut state, false);
+            add_round_key(&mut state, round_key);
+        }
+
+        // 3) final round without MixColumns
+        sub_bytes(&mut state, false);
+        shift_rows(&mut state, false);
+        add_round_key(&mut state, &self.round_keys[rounds]);
+
+        state_to_block(state)
+    }
+
+    // decrypts a single block
+    pub fn aes_decrypt_block(&self, c: u128) -> u128 {
+        let rounds = self.rounds();
+        let mut state = block_to_state(c);
+
+        // 1) undo the final round
+        add_round_key(&mut state, &self.round_keys[rounds]);
+        shift_rows(&mut state, true);
+        sub_bytes(&mut state, true);
+
+        // 2) undo the Nr - 1 full rounds in reverse order
+        for round_key in self.round_keys[1..rounds].iter().rev() {
+            add_round_key(&mut state, round_key);
+            mix_columns(&mut state, true);
+            shift_rows(&mut state, true);
+            sub_bytes(&mut state, true);
+        }
+
+        // 3) undo the initial round key addition
+        add_round_key(&mut state, &self.round_keys[0]);
+
+        state_to_block(state)
+    }
+
+    // number of rounds as defined by FIPS 197 based on the key length
+    fn rounds_for_key(key: &[u8]) -> Result<usize> {
+        match key.len() {
+            16 => Ok(10),
+            24 => Ok(12),
+            32 => Ok(14),
+            len => Err(Error::InvalidKeyLength(len)),
+        }
+    }
+}
+
+#[test]
+// FIPS 197 Appendix C
+fn test_known_aes_vectors() {
+    let plaintext: u128 = 0x00112233445566778899aabbccddeeff;
+    let key: Vec<u8> = (0u8..32).collect();
+
+    // (key length, expected ciphertext)
+    let vectors: [(usize, u128); 3] = [
+        (16, 0x69c4e0d86a7b0430d8cdb78070b4c55a),  // AES-128 (C.1)
+        (24, 0xdda97ca4864cdfe06eaf70a0ec0d7191),  // AES-192 (C.2)
+        (32, 0x8ea2b7ca516745bfeafc49904b496089),  // AES-256 (C.3)
+    ];
+
+    for (key_len, expected) in vectors {
+        let cipher = AES::new(&key[..key_len]).unwrap();
+        let ciphertext = cipher.encrypt_block(plaintext);
+        assert_eq!(
+            ciphertext, expected,
+            "Known AES-{} vector failed encryption", key_len * 8
+        );
+
+        let result = cipher.decrypt_block(ciphertext);
+        assert_eq!(
+            result, plaintext,
+            "Known AES-{} vector failed decryption", key_len * 8
+        );
+    }
+
+    // any other key length is rejected
+    assert_eq!(AES::new(&key[..20]).err(), Some(Error::InvalidKeyLength(20)));
+}
+
+#[test]
+// NIST SP 800-38A F.1.1 and F.2.1 (first two blocks)
+fn test_aes_operation_modes() {
+    use crate::{CipherOperationMode, ECB, CBC};
+
+    let key: [u8; 16] = 0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes();
+    let plaintext = [
+        0x6bc1bee22e409f96e93d7e117393172au128.to_be_bytes(),
+        0xae2d8a571e03ac9c9eb76fac45af8e51u128.to_be_bytes(),
+    ].concat();
+    let cipher = AES::new(&key).unwrap();
+
+    // ECB
+    let ciphertext = ECB.encrypt(&cipher, &plaintext).unwrap();
+    let expected = [
+        0x3ad77bb40d7a3660a89ecaf32466ef97u128.to_be_bytes(),
+        0xf5d3d58503b9699de785895a96fdbaafu128.to_be_bytes(),
+    ].concat();
+    assert_eq!(ciphertext, expected, "AES-128 ECB failed encryption");
+    assert_eq!(ECB.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "AES-128 ECB failed decryption");
+
+    // CBC
+    let cbc = CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let ciphertext = cbc.encrypt(&cipher, &plaintext).unwrap();
+    let expected = [
+        0x7649abac8119b246cee98e9b12e9197du128.to_be_bytes(),
+        0x5086cb9b507219ee95db113a917678b2u128.to_be_bytes(),
+    ].concat();
+    assert_eq!(ciphertext, expected, "AES-128 CBC failed encryption");
+    assert_eq!(cbc.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "AES-128 CBC failed decryption");
+}
@@ -0,0 +1,169 @@
+use crate::ciphers::aes::aes_constants::*;
+
+// multiplies a by x (0x02) in GF(2^8)
+// reducing modulo the AES polynomial x^8 + x^4 + x^3 + x + 1 (0x11b)
+pub fn xtime(a: u8) -> u8 {
+    let shifted = a << 1;
+    if a & 0x80 != 0 { shifted ^ 0x1b } else { shifted }
+}
+
+// multiplies a and b in GF(2^8) using the "russian peasant" method
+pub fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut out = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            out ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    out
+}
+
+// converts a 128 bit block into the AES state
+// NOTE: the first input byte is the most significant one
+pub fn block_to_state(block: u128) -> State {
+    block.to_be_bytes()
+}
+
+// converts the AES state back into a 128 bit block
+pub fn state_to_block(state: State) -> u128 {
+    u128::from_be_bytes(state)
+}
+
+// XORs the state with the round key
+pub fn add_round_key(state: &mut State, round_key: &RoundKey) {
+    for (s, k) in state.iter_mut().zip(round_key) {
+        *s ^= k;
+    }
+}
+
+// substitutes every byte of the state using the S-Box (or its inverse)
+pub fn sub_bytes(state: &mut State, inverse: bool) {
+    let sbox = if inverse { &INV_SBOX } else { &SBOX };
+    for s in state.iter_mut() {
+        *s = sbox[*s as usize];
+    }
+}
+
+// cyclically shifts row r of the state by r positions to the left
+// (or to the right if inverse is true)
+pub fn shift_rows(state: &mut State, inverse: bool) {
+    let old = *state;
+    for r in 1..4 {
+        for c in 0..NB {
+            let src_c = if inverse { (c + NB - r) % NB } else { (c + r) % NB };
+            state[4 * c + r] = old[4 * src_c + r];
+        }
+    }
+}
+
+// multiplies every column of the state by the fixed polynomial
+// a(x) = {03}x^3 + {01}x^2 + {01}x + {02} (or its inverse a^-1(x) if inverse is true)
+pub fn mix_columns(state: &mut State, inverse: bool) {
+    let coefficients: [u8; 4] = if inverse {
+        [0x0e, 0x0b, 0x0d, 0x09]
+    } else {
+        [0x02, 0x03, 0x01, 0x01]
+    };
+
+    for column in state.chunks_mut(4) {
+        let old = [column[0], column[1], column[2], column[3]];
+        for (r, out) in column.iter_mut().enumerate() {
+            // every row of the matrix is the previous one rotated by one position
+            *out = (0..4).fold(0u8, |acc, i| acc ^ gmul(coefficients[(i + 4 - r) % 4], old[i]));
+        }
+    }
+}
+
+// applies the S-Box to every byte of a 4 byte word
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    word.map(|b| SBOX[b as usize])
+}
+
+// expands a 128, 192 or 256 bit key into the Nr + 1 round keys
+pub fn expand_key(key: &[u8], rounds: usize) -> Vec<RoundKey> {
+    // number of 32 bit words in the key
+    let nk = key.len() / 4;
+    // total number of words to generate
+    let total = NB * (rounds + 1);
+
+    // 1. the first nk words are the key itself
+    let mut words: Vec<[u8; 4]> = key
+        .chunks(4)
+        .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]])
+        .collect();
+
+    // 2. every following word is w[i - nk] XOR a transformation of w[i - 1]
+    for i in nk..total {
+        let mut temp = words[i - 1];
+        if i % nk == 0 {
+            // RotWord, SubWord and XOR with the round constant
+            temp.rotate_left(1);
+            temp = sub_word(temp);
+            temp[0] ^= RCON[i / nk - 1];
+        } else if nk > 6 && i % nk == 4 {
+            // AES-256 applies an extra SubWord in the middle of every key block
+            temp = sub_word(temp);
+        }
+        let prev = words[i - nk];
+        words.push([prev[0] ^ temp[0], prev[1] ^ temp[1], prev[2] ^ temp[2], prev[3] ^ temp[3]]);
+    }
+
+    // 3. group the words 4 by 4 into the round keys
+    words
+        .chunks(NB)
+        .map(|w| {
+            let mut round_key = [0u8; 16];
+            for (c, word) in w.iter().enumerate() {
+                round_key[4 * c..4 * c + 4].copy_from_slice(word);
+            }
+            round_key
+        })
+        .collect()
+}
+
+// Unit Tests
+#[test]
+// FIPS 197 section 4.2.1
+fn test_gmul_works() {
+    assert_eq!(xtime(0x57), 0xae);
+    assert_eq!(xtime(0xae), 0x47);
+    assert_eq!(gmul(0x57, 0x13), 0xfe);
+    assert_eq!(gmul(0x57, 0x83), 0xc1);
+}
+
+#[test]
+fn test_mix_columns_inverse() {
+    // https://en.wikipedia.org/wiki/Rijndael_MixColumns#Test_vectors_for_MixColumn()
+    let mut state: State = [
+        0xdb, 0x13, 0x53, 0x45, 0xf2, 0x0a, 0x22, 0x5c,
+        0x01, 0x01, 0x01, 0x01, 0xc6, 0xc6, 0xc6, 0xc6,
+    ];
+    let expected: State = [
+        0x8e, 0x4d, 0xa1, 0xbc, 0x9f, 0xdc, 0x58, 0x9d,
+        0x01, 0x01, 0x01, 0x01, 0xc6, 0xc6, 0xc6, 0xc6,
+    ];
+    let original = state;
+    mix_columns(&mut state, false);
+    assert_eq!(state, expected, "Wrong MixColumns");
+    mix_columns(&mut state, true);
+    assert_eq!(state, original, "Wrong InvMixColumns");
+}
+
+#[test]
+// FIPS 197 Appendix A.1
+fn test_key_expansion_128() {
+    let key: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+        0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+    ];
+    let round_keys = expand_key(&key, 10);
+    assert_eq!(round_keys.len(), 11);
+    assert_eq!(round_keys[0], key);
+    assert_eq!(
+        u128::from_be_bytes(round_keys[10]),
+        0xd014f9a8c9ee2589e13f0cc8b6630ca6,
+        "Wrong last round key"
+    );
+}
@@ -0,0 +1,8 @@
+// exposed to other modules outside aes/
+pub mod aes;
+
+// local to aes/ module
+mod aes_constants;
+mod aes_utils;
+
+pub use aes::AES;
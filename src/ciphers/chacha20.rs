@@ -0,0 +1,283 @@
+/*
+* ChaCha20 stream cipher as defined by RFC 8439
+*
+* Carachteristics:
+* - 256 bit key, 96 bit nonce, 32 bit block counter (256 GiB per nonce)
+* - 512 bit state of 16 words: 4 constants, 8 key words, counter, 3 nonce words
+* - 20 rounds (10 column + diagonal double rounds) of add-rotate-xor quarter rounds
+* - keystream block = state + permuted state
+* - XChaCha20: 192 bit nonce, the key is first mixed with 128 bits of it by HChaCha20
+*   so random nonces can be used without worrying about collisions
+*/
+
+use crate::{Error, Result, StreamCipher};
+
+// "expand 32-byte k"
+pub(crate) const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+const BLOCK_SIZE: usize = 64;
+
+pub struct ChaCha20 {
+    key: [u32; 8],
+    nonce: [u32; 3],
+    // counter of the first keystream block
+    initial_counter: u32,
+    // byte offset in the keystream
+    position: u64,
+}
+
+impl StreamCipher for ChaCha20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) -> Result<()> {
+        // the last byte must still be covered by the 32 bit counter
+        let end = self.position + data.len() as u64;
+        let blocks_left = (1u64 << 32) - self.initial_counter as u64;
+        if end > blocks_left * BLOCK_SIZE as u64 {
+            return Err(Error::CounterOverflow);
+        }
+
+        let mut done = 0;
+        while done < data.len() {
+            let block_index = self.position / BLOCK_SIZE as u64;
+            let offset = (self.position % BLOCK_SIZE as u64) as usize;
+            let keystream = self.block(self.initial_counter + block_index as u32);
+
+            let n = (BLOCK_SIZE - offset).min(data.len() - done);
+            for (byte, k) in data[done..done + n].iter_mut().zip(&keystream[offset..]) {
+                *byte ^= k;
+            }
+            done += n;
+            self.position += n as u64;
+        }
+        Ok(())
+    }
+
+    fn seek(&mut self, position: u64) -> Result<()> {
+        let blocks_left = (1u64 << 32) - self.initial_counter as u64;
+        if position > blocks_left * BLOCK_SIZE as u64 {
+            return Err(Error::CounterOverflow);
+        }
+        self.position = position;
+        Ok(())
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl ChaCha20 {
+    // key: 32 bytes, nonce: 12 bytes, counter: value of the first block counter
+    pub fn new(key: &[u8], nonce: &[u8], counter: u32) -> Result<Self> {
+        let key = words::<8>(key).ok_or(Error::InvalidKeyLength(key.len()))?;
+        let nonce = words::<3>(nonce).ok_or(Error::InvalidParameter("ChaCha20 nonce must be 12 bytes"))?;
+        Ok(Self { key, nonce, initial_counter: counter, position: 0 })
+    }
+
+    // ChaCha20 block function: the 64 bytes keystream block for the given counter
+    pub fn block(&self, counter: u32) -> [u8; BLOCK_SIZE] {
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&SIGMA);
+        state[4..12].copy_from_slice(&self.key);
+        state[12] = counter;
+        state[13..].copy_from_slice(&self.nonce);
+
+        let mut working = state;
+        permute(&mut working, 20);
+
+        let mut out = [0u8; BLOCK_SIZE];
+        for (chunk, (w, s)) in out.chunks_exact_mut(4).zip(working.iter().zip(state)) {
+            chunk.copy_from_slice(&w.wrapping_add(s).to_le_bytes());
+        }
+        out
+    }
+}
+
+// XChaCha20: ChaCha20 keyed with HChaCha20(key, nonce[..16]) and nonce 0^32 || nonce[16..]
+pub struct XChaCha20(ChaCha20);
+
+impl XChaCha20 {
+    // key: 32 bytes, nonce: 24 bytes, counter: value of the first block counter
+    pub fn new(key: &[u8], nonce: &[u8], counter: u32) -> Result<Self> {
+        if nonce.len() != 24 {
+            return Err(Error::InvalidParameter("XChaCha20 nonce must be 24 bytes"));
+        }
+        let subkey = hchacha20(key, &nonce[..16])?;
+        let chacha_nonce = [&[0u8; 4][..], &nonce[16..]].concat();
+        Ok(Self(ChaCha20::new(&subkey, &chacha_nonce, counter)?))
+    }
+}
+
+impl StreamCipher for XChaCha20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) -> Result<()> {
+        self.0.apply_keystream(data)
+    }
+
+    fn seek(&mut self, position: u64) -> Result<()> {
+        self.0.seek(position)
+    }
+
+    fn position(&self) -> u64 {
+        self.0.position()
+    }
+}
+
+// HChaCha20: 20 rounds over (key, 16 bytes input) without the final addition,
+// outputs the first and last rows as a new 32 bytes key
+pub fn hchacha20(key: &[u8], input: &[u8]) -> Result<[u8; 32]> {
+    let key = words::<8>(key).ok_or(Error::InvalidKeyLength(key.len()))?;
+    let input = words::<4>(input).ok_or(Error::InvalidParameter("HChaCha20 input must be 16 bytes"))?;
+
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&SIGMA);
+    state[4..12].copy_from_slice(&key);
+    state[12..].copy_from_slice(&input);
+    permute(&mut state, 20);
+
+    let mut out = [0u8; 32];
+    for (chunk, w) in out.chunks_exact_mut(4).zip(state[..4].iter().chain(&state[12..])) {
+        chunk.copy_from_slice(&w.to_le_bytes());
+    }
+    Ok(out)
+}
+
+// quarter round on the words a, b, c, d of the state
+pub(crate) fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+// rounds / 2 double rounds (column round + diagonal round), without the final addition
+pub(crate) fn permute(state: &mut [u32; 16], rounds: usize) {
+    for _ in 0..rounds / 2 {
+        // column round
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 1, 5, 9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        // diagonal round
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7, 8, 13);
+        quarter_round(state, 3, 4, 9, 14);
+    }
+}
+
+// reads exactly N little-endian words from bytes
+pub(crate) fn words<const N: usize>(bytes: &[u8]) -> Option<[u32; N]> {
+    if bytes.len() != 4 * N {
+        return None;
+    }
+    let mut out = [0u32; N];
+    for (w, chunk) in out.iter_mut().zip(bytes.chunks_exact(4)) {
+        *w = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    Some(out)
+}
+
+#[test]
+// RFC 8439 2.1.1 quarter round test vector
+fn test_chacha20_quarter_round() {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&[0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567]);
+    quarter_round(&mut state, 0, 1, 2, 3);
+    assert_eq!(state[..4], [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb]);
+}
+
+#[test]
+// RFC 8439 2.3.2 block function test vector
+fn test_chacha20_block() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key: Vec<u8> = (0..32).collect();
+    let cipher = ChaCha20::new(&key, &hex_decode("000000090000004a00000000").unwrap(), 1).unwrap();
+    assert_eq!(hex_encode(&cipher.block(1)), "\
+        10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+        d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e");
+}
+
+#[test]
+// RFC 8439 2.4.2 encryption test vector
+fn test_chacha20_encryption() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key: Vec<u8> = (0..32).collect();
+    let nonce = hex_decode("000000000000004a00000000").unwrap();
+    let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let expected = "\
+        6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+        f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+        07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+        5af90bbf74a35be6b40b8eedf2785e42874d";
+
+    let mut data = plaintext.to_vec();
+    ChaCha20::new(&key, &nonce, 1).unwrap().apply_keystream(&mut data).unwrap();
+    assert_eq!(hex_encode(&data), expected);
+
+    // uneven pieces give the same result
+    let mut cipher = ChaCha20::new(&key, &nonce, 1).unwrap();
+    let mut data = plaintext.to_vec();
+    for chunk in data.chunks_mut(7) {
+        cipher.apply_keystream(chunk).unwrap();
+    }
+    assert_eq!(hex_encode(&data), expected);
+    assert_eq!(cipher.position(), plaintext.len() as u64);
+
+    // seeking back and applying it again decrypts
+    cipher.seek(0).unwrap();
+    cipher.apply_keystream(&mut data).unwrap();
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn test_chacha20_seek_and_errors() {
+    let key = [0x42; 32];
+    let nonce = [0x24; 12];
+
+    // seeking into the middle of a block matches the full keystream
+    let mut full = [0u8; 200];
+    ChaCha20::new(&key, &nonce, 0).unwrap().apply_keystream(&mut full).unwrap();
+    let mut cipher = ChaCha20::new(&key, &nonce, 0).unwrap();
+    cipher.seek(100).unwrap();
+    let mut tail = [0u8; 100];
+    cipher.apply_keystream(&mut tail).unwrap();
+    assert_eq!(tail, full[100..]);
+
+    // the counter can't wrap around
+    let mut cipher = ChaCha20::new(&key, &nonce, u32::MAX).unwrap();
+    assert_eq!(cipher.apply_keystream(&mut [0u8; 64]), Ok(()));
+    assert_eq!(cipher.apply_keystream(&mut [0u8; 1]), Err(Error::CounterOverflow));
+    assert_eq!(cipher.seek(65), Err(Error::CounterOverflow));
+
+    assert_eq!(ChaCha20::new(&[0; 16], &nonce, 0).err(), Some(Error::InvalidKeyLength(16)));
+    assert!(matches!(ChaCha20::new(&key, &[0; 8], 0), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+// draft-irtf-cfrg-xchacha 2.2.1 HChaCha20 and A.3.2 XChaCha20 test vectors
+fn test_xchacha20_known_vectors() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key: Vec<u8> = (0..32).collect();
+    let input = hex_decode("000000090000004a0000000031415927").unwrap();
+    assert_eq!(
+        hex_encode(&hchacha20(&key, &input).unwrap()),
+        "82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc"
+    );
+
+    let key: Vec<u8> = (0x80..0xa0).collect();
+    let nonce = hex_decode("404142434445464748494a4b4c4d4e4f5051525354555658").unwrap();
+    let mut data = b"The dhole (pronounced \"dole\") is also known as the Asiatic wild dog, red dog, and whistling dog.".to_vec();
+    XChaCha20::new(&key, &nonce, 1).unwrap().apply_keystream(&mut data).unwrap();
+    assert_eq!(hex_encode(&data), "\
+        7d0a2e6b7f7c65a236542630294e063b7ab9b555a5d5149aa21e4ae1e4fbce87\
+        ecc8e08a8b5e350abe622b2ffa617b202cfad72032a3037e76ffdcdc4376ee05\
+        3a190d7e46ca1de04144850381b9cb29f051915386b8a710b8ac4d027b8b050f");
+
+    assert!(matches!(XChaCha20::new(&key, &nonce[..12], 0), Err(Error::InvalidParameter(_))));
+}
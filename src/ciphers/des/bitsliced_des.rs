@@ -0,0 +1,296 @@
+/*
+* Bitsliced DES: 64 blocks (or 64 keys) processed in parallel
+*
+* Carachteristics:
+* - the state is stored transposed: word j holds bit j (FIPS bit j + 1) of all the 64 blocks,
+*   bit 63 - b of each word (the "lane" b) belongs to block b
+* - blocks and words are converted with a 64x64 bit matrix transpose (6 stages of delta swaps)
+* - IP, IP^-1, E, P and the key schedule become plain wiring (indexing into the words)
+* - the S-boxes are straight line boolean circuits on the words (see bitsliced_sboxes)
+* - BitslicedKeySearch gives every lane a different key, which is what a key search needs:
+*   it isn't a single permutation, so unlike BitslicedDES it doesn't implement BlockCipher
+*
+* NOTE it only pays off with many blocks: a single encrypt_block still runs all the 64 lanes
+*/
+
+use crate::BlockCipher;
+use crate::ciphers::des::des_constants::*;
+use crate::ciphers::des::bitsliced_sboxes::*;
+
+// number of blocks (or keys) processed in parallel
+pub const LANES: usize = 64;
+
+// KEY_BITS[round][i]: position of the key bit used by bit i of the round key
+const KEY_BITS: [[u8; 48]; FEISTEL_ROUNDS] = build_key_bits();
+
+// follows every round key bit back through PC-2, the rotations and PC-1
+const fn build_key_bits() -> [[u8; 48]; FEISTEL_ROUNDS] {
+    let mut bits = [[0u8; 48]; FEISTEL_ROUNDS];
+    let mut shift = 0;
+    let mut round = 0;
+    while round < FEISTEL_ROUNDS {
+        shift += ITER_SX_SHIFT[round] as usize;
+        let mut i = 0;
+        while i < 48 {
+            // position in C || D after the rotations of this round
+            let pos = PC2[i] as usize - 1;
+            // position in C || D before any rotation (each half rotates on its own)
+            let half = pos / 28 * 28;
+            let pos = half + (pos - half + shift) % 28;
+            bits[round][i] = PC1[pos] - 1;
+            i += 1;
+        }
+        round += 1;
+    }
+    bits
+}
+
+// round keys, one word for each bit of each round key
+struct BitslicedSchedule {
+    round_keys: [[u64; 48]; FEISTEL_ROUNDS],
+}
+
+// DES with a single key, 64 blocks at a time
+pub struct BitslicedDES {
+    schedule: BitslicedSchedule,
+}
+
+// DES with a different key in each of the 64 lanes
+pub struct BitslicedKeySearch {
+    schedule: BitslicedSchedule,
+}
+
+impl BlockCipher for BitslicedDES {
+    type Block = u64;
+
+    fn encrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.encrypt_lanes(&[block; LANES])[0]
+    }
+
+    fn decrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.decrypt_lanes(&[block; LANES])[0]
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        self.process_blocks(blocks, false);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        self.process_blocks(blocks, true);
+    }
+}
+
+impl BitslicedDES {
+    // initialize the cipher
+    pub fn new(key: u64) -> Self {
+        // a word full of ones for every key bit set, zero otherwise
+        let key_words: [u64; 64] = std::array::from_fn(|j| 0u64.wrapping_sub((key >> (63 - j)) & 0x1));
+        Self { schedule: BitslicedSchedule::new(&key_words) }
+    }
+
+    // change the secret key to use
+    pub fn change_key(&mut self, new_key: u64) {
+        *self = Self::new(new_key);
+    }
+
+    // encrypts 64 blocks
+    pub fn encrypt_lanes(&self, blocks: &[u64; LANES]) -> [u64; LANES] {
+        self.schedule.process_lanes(blocks, false)
+    }
+
+    // decrypts 64 blocks
+    pub fn decrypt_lanes(&self, blocks: &[u64; LANES]) -> [u64; LANES] {
+        self.schedule.process_lanes(blocks, true)
+    }
+
+    // processes the blocks 64 at a time, the last batch is padded with zeros
+    fn process_blocks(&self, blocks: &mut [u64], decrypt: bool) {
+        for chunk in blocks.chunks_mut(LANES) {
+            let mut batch = [0u64; LANES];
+            batch[..chunk.len()].copy_from_slice(chunk);
+            let out = self.schedule.process_lanes(&batch, decrypt);
+            chunk.copy_from_slice(&out[..chunk.len()]);
+        }
+    }
+}
+
+impl BitslicedKeySearch {
+    // keys[i] is used for the block in lane i
+    pub fn new(keys: &[u64; LANES]) -> Self {
+        Self { schedule: BitslicedSchedule::new(&to_slices(keys)) }
+    }
+
+    // encrypts 64 blocks, block i with keys[i]
+    pub fn encrypt_lanes(&self, blocks: &[u64; LANES]) -> [u64; LANES] {
+        self.schedule.process_lanes(blocks, false)
+    }
+
+    // decrypts 64 blocks, block i with keys[i]
+    pub fn decrypt_lanes(&self, blocks: &[u64; LANES]) -> [u64; LANES] {
+        self.schedule.process_lanes(blocks, true)
+    }
+}
+
+impl BitslicedSchedule {
+    // key_words[j]: bit j (from the MSB) of the key of every lane
+    fn new(key_words: &[u64; 64]) -> Self {
+        let round_keys = std::array::from_fn(|round| {
+            std::array::from_fn(|i| key_words[KEY_BITS[round][i] as usize])
+        });
+        Self { round_keys }
+    }
+
+    fn process_lanes(&self, blocks: &[u64; LANES], decrypt: bool) -> [u64; LANES] {
+        from_slices(&self.run_feistel_network(&to_slices(blocks), decrypt))
+    }
+
+    fn run_feistel_network(&self, block: &[u64; 64], decrypt: bool) -> [u64; 64] {
+        // 1) the initial permutation is just a reordering of the words
+        let mut l: [u64; 32] = std::array::from_fn(|i| block[IP[i] as usize - 1]);
+        let mut r: [u64; 32] = std::array::from_fn(|i| block[IP[32 + i] as usize - 1]);
+
+        // 2) apply 16 rounds, with the round keys reversed for decryption
+        for round in 0..FEISTEL_ROUNDS {
+            let round_key = if decrypt { &self.round_keys[FEISTEL_ROUNDS - 1 - round] } else { &self.round_keys[round] };
+            let f = round_function(&r, round_key);
+            let tmp: [u64; 32] = std::array::from_fn(|i| l[i] ^ f[i]);
+            l = r;
+            r = tmp;
+        }
+
+        // 3) combine back (R16 || L16) and apply the inverse of the initial permutation
+        let preoutput = |i: usize| if i < 32 { r[i] } else { l[i - 32] };
+        std::array::from_fn(|i| preoutput(IP_INVERSE[i] as usize - 1))
+    }
+}
+
+// round function on 32 words: E, XOR with the round key, S-boxes and P
+fn round_function(r: &[u64; 32], round_key: &[u64; 48]) -> [u64; 32] {
+    // E selects the 6 input words of S-box s
+    let input = |s: usize| -> [u64; 6] {
+        std::array::from_fn(|j| r[E[6 * s + j] as usize - 1] ^ round_key[6 * s + j])
+    };
+    let out = [
+        s1(input(0)), s2(input(1)), s3(input(2)), s4(input(3)),
+        s5(input(4)), s6(input(5)), s7(input(6)), s8(input(7)),
+    ];
+    std::array::from_fn(|i| {
+        let bit = P[i] as usize - 1;
+        out[bit / 4][bit % 4]
+    })
+}
+
+// (shift, mask) of the 6 stages of the transpose
+const TRANSPOSE_STAGES: [(usize, u64); 6] = [
+    (32, 0x00000000FFFFFFFF),
+    (16, 0x0000FFFF0000FFFF),
+    (8, 0x00FF00FF00FF00FF),
+    (4, 0x0F0F0F0F0F0F0F0F),
+    (2, 0x3333333333333333),
+    (1, 0x5555555555555555),
+];
+
+// transposes a 64x64 bit matrix (row i is words[i], column j is bit 63 - j): bit 63 - i of word j
+// becomes bit 63 - j of word i. Each stage swaps the off diagonal blocks of the sub matrices of
+// size 2 * shift, between the rows k and k + shift
+fn transpose(mut words: [u64; 64]) -> [u64; 64] {
+    for (shift, mask) in TRANSPOSE_STAGES {
+        for k in (0..64).filter(|k| k & shift == 0) {
+            let t = (words[k] ^ (words[k + shift] >> shift)) & mask;
+            words[k] ^= t;
+            words[k + shift] ^= t << shift;
+        }
+    }
+    words
+}
+
+// transposes 64 blocks into 64 words: bit 63 - b of word j is bit j (from the MSB) of block b
+fn to_slices(blocks: &[u64; 64]) -> [u64; 64] {
+    transpose(*blocks)
+}
+
+// inverse of to_slices (the transpose is an involution)
+fn from_slices(words: &[u64; 64]) -> [u64; 64] {
+    transpose(*words)
+}
+
+#[test]
+// https://page.math.tu-berlin.de/~kant/teaching/hess/krypto-ws2006/des.htm
+fn test_bitsliced_known_des_vector() {
+    let cipher = BitslicedDES::new(0x133457799BBCDFF1);
+    assert_eq!(cipher.encrypt_block(0x0123456789ABCDEF), 0x85E813540F0AB405);
+    assert_eq!(cipher.decrypt_block(0x85E813540F0AB405), 0x0123456789ABCDEF);
+}
+
+#[test]
+// the delta swap transpose must match moving the 4096 bits one at a time
+fn test_transpose_roundtrip() {
+    let blocks: [u64; 64] = std::array::from_fn(|i| (i as u64 + 1).wrapping_mul(0x9E3779B97F4A7C15));
+    let expected: [u64; 64] = std::array::from_fn(|j| {
+        blocks.iter().enumerate().fold(0, |acc, (b, block)| acc | ((block >> (63 - j)) & 0x1) << (63 - b))
+    });
+
+    let slices = to_slices(&blocks);
+    assert_eq!(slices, expected);
+    assert_eq!((slices[0] >> (63 - 5)) & 0x1, blocks[5] >> 63);
+    assert_eq!(from_slices(&slices), blocks);
+}
+
+#[test]
+// every lane must match DES::des_encrypt_block, with the same key and with a key per lane
+fn test_bitsliced_matches_des() {
+    use crate::DES;
+
+    let blocks: [u64; 64] = std::array::from_fn(|i| (i as u64).wrapping_mul(0x9E3779B97F4A7C15) ^ 0x0123456789ABCDEF);
+    let keys: [u64; 64] = std::array::from_fn(|i| (i as u64 + 7).wrapping_mul(0xD1B54A32D192ED03));
+
+    let cipher = BitslicedDES::new(keys[0]);
+    let ciphertexts = cipher.encrypt_lanes(&blocks);
+    for (i, &ciphertext) in ciphertexts.iter().enumerate() {
+        assert_eq!(ciphertext, DES::new(keys[0]).des_encrypt_block(blocks[i]), "lane {}", i);
+    }
+    assert_eq!(cipher.decrypt_lanes(&ciphertexts), blocks);
+
+    let cipher = BitslicedKeySearch::new(&keys);
+    let ciphertexts = cipher.encrypt_lanes(&blocks);
+    for (i, &ciphertext) in ciphertexts.iter().enumerate() {
+        assert_eq!(ciphertext, DES::new(keys[i]).des_encrypt_block(blocks[i]), "lane {}", i);
+    }
+    assert_eq!(cipher.decrypt_lanes(&ciphertexts), blocks);
+}
+
+#[test]
+// batches of any size through the BlockCipher interface (ECB and CTR)
+fn test_bitsliced_modes() {
+    use crate::{CipherOperationMode, DES, ECB, CTR};
+
+    let key = 0x133457799BBCDFF1;
+    let plaintext: Vec<u8> = (0..8 * 150).map(|i| (i * 7) as u8).collect();
+
+    let ecb = ECB.encrypt(&BitslicedDES::new(key), &plaintext).unwrap();
+    assert_eq!(ecb, ECB.encrypt(&DES::new(key), &plaintext).unwrap());
+    assert_eq!(ECB.decrypt(&BitslicedDES::new(key), &ecb).unwrap(), plaintext);
+
+    let ctr = CTR::<BitslicedDES>::new(&[0xAB, 0xCD, 0xEF, 0x01], 32).unwrap();
+    let reference = CTR::<DES>::new(&[0xAB, 0xCD, 0xEF, 0x01], 32).unwrap();
+    assert_eq!(
+        ctr.encrypt(&BitslicedDES::new(key), &plaintext[..1001]).unwrap(),
+        reference.encrypt(&DES::new(key), &plaintext[..1001]).unwrap()
+    );
+}
+
+#[test]
+// toy key search: the 64 candidates differ in 6 unknown key bits (not parity bits)
+fn test_bitsliced_key_search() {
+    use crate::DES;
+
+    let secret = 0x133457799BBCDFF1u64;
+    let plaintext = 0x0123456789ABCDEF;
+    let ciphertext = DES::new(secret).des_encrypt_block(plaintext);
+
+    // the attacker knows everything but bits 2..7 of the last key byte (bit 0 is parity)
+    let candidates: [u64; 64] = std::array::from_fn(|i| (secret & !0xFC) | ((i as u64) << 2));
+    let results = BitslicedKeySearch::new(&candidates).encrypt_lanes(&[plaintext; LANES]);
+    let found: Vec<u64> = (0..LANES).filter(|&i| results[i] == ciphertext).map(|i| candidates[i]).collect();
+    assert_eq!(found, [secret]);
+}
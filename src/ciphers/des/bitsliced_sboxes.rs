@@ -0,0 +1,861 @@
+/*
+* DES S-boxes as straight line boolean circuits, for the bitsliced implementation
+*
+* Carachteristics:
+* - s1..s8 take the 6 input words (a is the most significant input bit) and return the
+*   4 output words (most significant output bit first), every bit position is an independent evaluation
+* - each circuit is a chain of multiplexers (Shannon expansion) on the inputs, in the
+*   input order that gave the fewest gates, with shared subexpressions merged across the 4 outputs
+*   and the trivial multiplexers (constant or complementary inputs) reduced to a single AND, OR or XOR
+* - only AND, OR, XOR and NOT, no lookups and no branches: constant time
+*
+* NOTE the circuits were derived from the FIPS tables in des_constants, test_sbox_circuits checks every input
+*/
+
+// 110 gates
+pub fn s1([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = !b;
+    let x1 = e ^ b;
+    let x2 = x0 ^ x1;
+    let x3 = x2 & c;
+    let x4 = x0 ^ x3;
+    let x5 = x2 | b;
+    let x6 = e & b;
+    let x7 = x5 ^ x3;
+    let x8 = x4 ^ x7;
+    let x9 = x8 & d;
+    let x10 = x4 ^ x9;
+    let x11 = !x1;
+    let x12 = x0 & c;
+    let x13 = e ^ x12;
+    let x14 = !x6;
+    let x15 = !x8;
+    let x16 = x1 & c;
+    let x17 = x14 ^ x16;
+    let x18 = x7 & d;
+    let x19 = x13 ^ x18;
+    let x20 = x10 ^ x19;
+    let x21 = x20 & f;
+    let x22 = x10 ^ x21;
+    let x23 = x8 & c;
+    let x24 = x5 ^ x23;
+    let x25 = x2 & b;
+    let x26 = x5 & c;
+    let x27 = x11 ^ x26;
+    let x28 = x24 ^ x27;
+    let x29 = x28 & d;
+    let x30 = x24 ^ x29;
+    let x31 = x17 ^ d;
+    let x32 = x30 ^ x31;
+    let x33 = x32 & f;
+    let x34 = x30 ^ x33;
+    let x35 = x22 ^ x34;
+    let x36 = x35 & a;
+    let x37 = x22 ^ x36;
+    let x38 = x25 ^ x26;
+    let x39 = x24 ^ x38;
+    let x40 = x39 & d;
+    let x41 = x24 ^ x40;
+    let x42 = x8 ^ x26;
+    let x43 = x14 & d;
+    let x44 = x42 ^ x43;
+    let x45 = x41 ^ x44;
+    let x46 = x45 & f;
+    let x47 = x41 ^ x46;
+    let x48 = e & c;
+    let x49 = x25 ^ x48;
+    let x50 = x11 ^ x48;
+    let x51 = x5 & d;
+    let x52 = x49 ^ x51;
+    let x53 = x6 & c;
+    let x54 = x8 ^ x53;
+    let x55 = x27 ^ x54;
+    let x56 = x55 & d;
+    let x57 = x27 ^ x56;
+    let x58 = x52 ^ x57;
+    let x59 = x58 & f;
+    let x60 = x52 ^ x59;
+    let x61 = x47 ^ x60;
+    let x62 = x61 & a;
+    let x63 = x47 ^ x62;
+    let x64 = x39 ^ x50;
+    let x65 = x64 & d;
+    let x66 = x39 ^ x65;
+    let x67 = !x39;
+    let x68 = !x4;
+    let x69 = x67 ^ x68;
+    let x70 = x69 & d;
+    let x71 = x67 ^ x70;
+    let x72 = x66 ^ x71;
+    let x73 = x72 & f;
+    let x74 = x66 ^ x73;
+    let x75 = x68 ^ x55;
+    let x76 = x75 & d;
+    let x77 = x68 ^ x76;
+    let x78 = !x38;
+    let x79 = x15 & c;
+    let x80 = x11 ^ x79;
+    let x81 = x78 ^ x80;
+    let x82 = x81 & d;
+    let x83 = x78 ^ x82;
+    let x84 = x77 ^ x83;
+    let x85 = x84 & f;
+    let x86 = x77 ^ x85;
+    let x87 = x74 ^ x86;
+    let x88 = x87 & a;
+    let x89 = x74 ^ x88;
+    let x90 = x0 ^ x48;
+    let x91 = x49 ^ x43;
+    let x92 = !x24;
+    let x93 = x92 ^ x76;
+    let x94 = x91 ^ x93;
+    let x95 = x94 & f;
+    let x96 = x91 ^ x95;
+    let x97 = !x90;
+    let x98 = x67 ^ x97;
+    let x99 = x98 & d;
+    let x100 = x67 ^ x99;
+    let x101 = x5 ^ c;
+    let x102 = x11 & d;
+    let x103 = x101 ^ x102;
+    let x104 = x100 ^ x103;
+    let x105 = x104 & f;
+    let x106 = x100 ^ x105;
+    let x107 = x96 ^ x106;
+    let x108 = x107 & a;
+    let x109 = x96 ^ x108;
+    [x89, x37, x63, x109]
+}
+
+// 101 gates
+pub fn s2([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = !f;
+    let x1 = x0 ^ e;
+    let x2 = x1 ^ c;
+    let x3 = e & d;
+    let x4 = x2 ^ x3;
+    let x5 = f | e;
+    let x6 = x5 ^ x1;
+    let x7 = x6 & c;
+    let x8 = x5 ^ x7;
+    let x9 = !x1;
+    let x10 = !x5;
+    let x11 = x9 ^ x7;
+    let x12 = x8 ^ x11;
+    let x13 = x12 & d;
+    let x14 = x8 ^ x13;
+    let x15 = x4 ^ x14;
+    let x16 = x15 & a;
+    let x17 = x4 ^ x16;
+    let x18 = !e;
+    let x19 = f & c;
+    let x20 = x18 ^ x19;
+    let x21 = x20 ^ d;
+    let x22 = x11 ^ d;
+    let x23 = x21 ^ x22;
+    let x24 = x23 & a;
+    let x25 = x21 ^ x24;
+    let x26 = x17 ^ x25;
+    let x27 = x26 & b;
+    let x28 = x17 ^ x27;
+    let x29 = x18 | c;
+    let x30 = x0 & c;
+    let x31 = e ^ x30;
+    let x32 = x29 ^ x31;
+    let x33 = x32 & d;
+    let x34 = x29 ^ x33;
+    let x35 = x0 & e;
+    let x36 = x35 ^ c;
+    let x37 = x18 & d;
+    let x38 = x36 ^ x37;
+    let x39 = x34 ^ x38;
+    let x40 = x39 & a;
+    let x41 = x34 ^ x40;
+    let x42 = f & x18;
+    let x43 = x9 & c;
+    let x44 = x35 ^ x43;
+    let x45 = x44 ^ x2;
+    let x46 = x45 & d;
+    let x47 = x44 ^ x46;
+    let x48 = x5 & c;
+    let x49 = f ^ x48;
+    let x50 = x10 & c;
+    let x51 = x9 ^ x50;
+    let x52 = x49 ^ x51;
+    let x53 = x52 & d;
+    let x54 = x49 ^ x53;
+    let x55 = x47 ^ x54;
+    let x56 = x55 & a;
+    let x57 = x47 ^ x56;
+    let x58 = x41 ^ x57;
+    let x59 = x58 & b;
+    let x60 = x41 ^ x59;
+    let x61 = x1 ^ x19;
+    let x62 = x42 & c;
+    let x63 = x5 ^ x62;
+    let x64 = x61 ^ x63;
+    let x65 = x64 & d;
+    let x66 = x61 ^ x65;
+    let x67 = x66 ^ a;
+    let x68 = x9 ^ x30;
+    let x69 = x35 ^ x1;
+    let x70 = x69 & c;
+    let x71 = x35 ^ x70;
+    let x72 = x68 ^ x71;
+    let x73 = x72 & d;
+    let x74 = x68 ^ x73;
+    let x75 = !x51;
+    let x76 = x75 ^ x32;
+    let x77 = x76 & d;
+    let x78 = x75 ^ x77;
+    let x79 = x74 ^ x78;
+    let x80 = x79 & a;
+    let x81 = x74 ^ x80;
+    let x82 = x67 ^ x81;
+    let x83 = x82 & b;
+    let x84 = x67 ^ x83;
+    let x85 = x6 & d;
+    let x86 = x32 ^ x85;
+    let x87 = x72 ^ d;
+    let x88 = x86 ^ x87;
+    let x89 = x88 & a;
+    let x90 = x86 ^ x89;
+    let x91 = x23 ^ x37;
+    let x92 = x18 ^ x48;
+    let x93 = x10 & d;
+    let x94 = x92 ^ x93;
+    let x95 = x91 ^ x94;
+    let x96 = x95 & a;
+    let x97 = x91 ^ x96;
+    let x98 = x90 ^ x97;
+    let x99 = x98 & b;
+    let x100 = x90 ^ x99;
+    [x28, x84, x60, x100]
+}
+
+// 101 gates
+pub fn s3([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = !e;
+    let x1 = x0 ^ b;
+    let x2 = f | x0;
+    let x3 = x2 & b;
+    let x4 = x1 ^ x3;
+    let x5 = x4 & c;
+    let x6 = x1 ^ x5;
+    let x7 = !f;
+    let x8 = x7 | e;
+    let x9 = x7 ^ e;
+    let x10 = x8 ^ x9;
+    let x11 = x10 & b;
+    let x12 = x8 ^ x11;
+    let x13 = x9 ^ b;
+    let x14 = x12 ^ x13;
+    let x15 = x14 & c;
+    let x16 = x12 ^ x15;
+    let x17 = x6 ^ x16;
+    let x18 = x17 & d;
+    let x19 = x6 ^ x18;
+    let x20 = !x9;
+    let x21 = x9 ^ x15;
+    let x22 = x21 ^ d;
+    let x23 = x19 ^ x22;
+    let x24 = x23 & a;
+    let x25 = x19 ^ x24;
+    let x26 = f ^ x10;
+    let x27 = x26 & b;
+    let x28 = f ^ x27;
+    let x29 = x28 ^ x13;
+    let x30 = x29 & c;
+    let x31 = x28 ^ x30;
+    let x32 = x7 | x0;
+    let x33 = x7 & b;
+    let x34 = x32 ^ x33;
+    let x35 = x14 ^ x34;
+    let x36 = x35 & c;
+    let x37 = x14 ^ x36;
+    let x38 = x31 ^ x37;
+    let x39 = x38 & d;
+    let x40 = x31 ^ x39;
+    let x41 = x7 ^ b;
+    let x42 = x0 & c;
+    let x43 = x41 ^ x42;
+    let x44 = x0 ^ x33;
+    let x45 = x2 & c;
+    let x46 = x44 ^ x45;
+    let x47 = x43 ^ x46;
+    let x48 = x47 & d;
+    let x49 = x43 ^ x48;
+    let x50 = x40 ^ x49;
+    let x51 = x50 & a;
+    let x52 = x40 ^ x51;
+    let x53 = x9 ^ x3;
+    let x54 = x32 ^ x27;
+    let x55 = x53 ^ x54;
+    let x56 = x55 & c;
+    let x57 = x53 ^ x56;
+    let x58 = x10 ^ e;
+    let x59 = x58 & b;
+    let x60 = x10 ^ x59;
+    let x61 = x60 ^ c;
+    let x62 = x57 ^ x61;
+    let x63 = x62 & d;
+    let x64 = x57 ^ x63;
+    let x65 = !x44;
+    let x66 = x65 ^ x20;
+    let x67 = x66 & c;
+    let x68 = x65 ^ x67;
+    let x69 = x9 ^ x27;
+    let x70 = x3 ^ x69;
+    let x71 = x70 & c;
+    let x72 = x3 ^ x71;
+    let x73 = x68 ^ x72;
+    let x74 = x73 & d;
+    let x75 = x68 ^ x74;
+    let x76 = x64 ^ x75;
+    let x77 = x76 & a;
+    let x78 = x64 ^ x77;
+    let x79 = !x41;
+    let x80 = e & c;
+    let x81 = x79 ^ x80;
+    let x82 = x0 & d;
+    let x83 = x81 ^ x82;
+    let x84 = x32 & b;
+    let x85 = e ^ x84;
+    let x86 = x35 ^ x85;
+    let x87 = x86 & c;
+    let x88 = x35 ^ x87;
+    let x89 = !x69;
+    let x90 = x8 & b;
+    let x91 = x9 ^ x90;
+    let x92 = x89 ^ x91;
+    let x93 = x92 & c;
+    let x94 = x89 ^ x93;
+    let x95 = x88 ^ x94;
+    let x96 = x95 & d;
+    let x97 = x88 ^ x96;
+    let x98 = x83 ^ x97;
+    let x99 = x98 & a;
+    let x100 = x83 ^ x99;
+    [x25, x52, x78, x100]
+}
+
+// 72 gates
+pub fn s4([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = !c;
+    let x1 = !d;
+    let x2 = x0 ^ x1;
+    let x3 = x2 & e;
+    let x4 = x0 ^ x3;
+    let x5 = d & c;
+    let x6 = d | c;
+    let x7 = x5 ^ x3;
+    let x8 = x4 ^ x7;
+    let x9 = x8 & b;
+    let x10 = x4 ^ x9;
+    let x11 = !x2;
+    let x12 = x1 & e;
+    let x13 = c ^ x12;
+    let x14 = x1 ^ e;
+    let x15 = x13 ^ x14;
+    let x16 = x15 & b;
+    let x17 = x13 ^ x16;
+    let x18 = x10 ^ x17;
+    let x19 = x18 & a;
+    let x20 = x10 ^ x19;
+    let x21 = x0 & e;
+    let x22 = x1 ^ x21;
+    let x23 = x22 ^ x15;
+    let x24 = x23 & b;
+    let x25 = x22 ^ x24;
+    let x26 = x8 & e;
+    let x27 = x5 ^ x26;
+    let x28 = x1 | c;
+    let x29 = x6 & b;
+    let x30 = x27 ^ x29;
+    let x31 = x25 ^ x30;
+    let x32 = x31 & a;
+    let x33 = x25 ^ x32;
+    let x34 = x20 ^ x33;
+    let x35 = x34 & f;
+    let x36 = x20 ^ x35;
+    let x37 = !x33;
+    let x38 = x37 ^ x20;
+    let x39 = x38 & f;
+    let x40 = x37 ^ x39;
+    let x41 = x13 & b;
+    let x42 = x15 ^ x41;
+    let x43 = d ^ x3;
+    let x44 = x28 & b;
+    let x45 = x43 ^ x44;
+    let x46 = x42 ^ x45;
+    let x47 = x46 & a;
+    let x48 = x42 ^ x47;
+    let x49 = x28 & e;
+    let x50 = x0 ^ x49;
+    let x51 = !x8;
+    let x52 = x51 ^ x49;
+    let x53 = x50 ^ x52;
+    let x54 = x53 & b;
+    let x55 = x50 ^ x54;
+    let x56 = c & e;
+    let x57 = x11 ^ x56;
+    let x58 = !x13;
+    let x59 = x57 ^ x58;
+    let x60 = x59 & b;
+    let x61 = x57 ^ x60;
+    let x62 = x55 ^ x61;
+    let x63 = x62 & a;
+    let x64 = x55 ^ x63;
+    let x65 = x48 ^ x64;
+    let x66 = x65 & f;
+    let x67 = x48 ^ x66;
+    let x68 = !x48;
+    let x69 = x64 ^ x68;
+    let x70 = x69 & f;
+    let x71 = x64 ^ x70;
+    [x40, x36, x71, x67]
+}
+
+// 109 gates
+pub fn s5([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = e ^ a;
+    let x1 = !x0;
+    let x2 = !e;
+    let x3 = a & b;
+    let x4 = x1 ^ x3;
+    let x5 = x0 ^ x4;
+    let x6 = x5 & c;
+    let x7 = x0 ^ x6;
+    let x8 = !a;
+    let x9 = x2 & x8;
+    let x10 = x2 | a;
+    let x11 = x9 ^ x3;
+    let x12 = e | x8;
+    let x13 = e & a;
+    let x14 = x8 & b;
+    let x15 = x12 ^ x14;
+    let x16 = x11 ^ x15;
+    let x17 = x16 & c;
+    let x18 = x11 ^ x17;
+    let x19 = x7 ^ x18;
+    let x20 = x19 & f;
+    let x21 = x7 ^ x20;
+    let x22 = x9 ^ b;
+    let x23 = x0 ^ b;
+    let x24 = x22 ^ x23;
+    let x25 = x24 & c;
+    let x26 = x22 ^ x25;
+    let x27 = x26 ^ f;
+    let x28 = x21 ^ x27;
+    let x29 = x28 & d;
+    let x30 = x21 ^ x29;
+    let x31 = x10 & b;
+    let x32 = x1 ^ x31;
+    let x33 = x12 & b;
+    let x34 = x24 ^ x33;
+    let x35 = x32 ^ x34;
+    let x36 = x35 & c;
+    let x37 = x32 ^ x36;
+    let x38 = x34 ^ x16;
+    let x39 = x38 & c;
+    let x40 = x34 ^ x39;
+    let x41 = x37 ^ x40;
+    let x42 = x41 & f;
+    let x43 = x37 ^ x42;
+    let x44 = !x34;
+    let x45 = x24 ^ x1;
+    let x46 = x45 & b;
+    let x47 = x24 ^ x46;
+    let x48 = x44 ^ x47;
+    let x49 = x48 & c;
+    let x50 = x44 ^ x49;
+    let x51 = e & b;
+    let x52 = x1 ^ x51;
+    let x53 = x52 ^ x39;
+    let x54 = x50 ^ x53;
+    let x55 = x54 & f;
+    let x56 = x50 ^ x55;
+    let x57 = x43 ^ x56;
+    let x58 = x57 & d;
+    let x59 = x43 ^ x58;
+    let x60 = x46 ^ x1;
+    let x61 = x60 & c;
+    let x62 = x46 ^ x61;
+    let x63 = x2 & b;
+    let x64 = x51 & c;
+    let x65 = x23 ^ x64;
+    let x66 = x62 ^ x65;
+    let x67 = x66 & f;
+    let x68 = x62 ^ x67;
+    let x69 = x45 ^ x63;
+    let x70 = !x10;
+    let x71 = x24 & b;
+    let x72 = x2 ^ x71;
+    let x73 = x69 ^ x72;
+    let x74 = x73 & c;
+    let x75 = x69 ^ x74;
+    let x76 = x9 & b;
+    let x77 = x13 ^ x76;
+    let x78 = x12 ^ x71;
+    let x79 = x77 ^ x78;
+    let x80 = x79 & c;
+    let x81 = x77 ^ x80;
+    let x82 = x75 ^ x81;
+    let x83 = x82 & f;
+    let x84 = x75 ^ x83;
+    let x85 = x68 ^ x84;
+    let x86 = x85 & d;
+    let x87 = x68 ^ x86;
+    let x88 = x70 ^ b;
+    let x89 = a & c;
+    let x90 = x88 ^ x89;
+    let x91 = !x71;
+    let x92 = !x47;
+    let x93 = x91 ^ x92;
+    let x94 = x93 & c;
+    let x95 = x91 ^ x94;
+    let x96 = x90 ^ x95;
+    let x97 = x96 & f;
+    let x98 = x90 ^ x97;
+    let x99 = x11 & c;
+    let x100 = x92 ^ x99;
+    let x101 = !x32;
+    let x102 = x101 ^ x49;
+    let x103 = x100 ^ x102;
+    let x104 = x103 & f;
+    let x105 = x100 ^ x104;
+    let x106 = x98 ^ x105;
+    let x107 = x106 & d;
+    let x108 = x98 ^ x107;
+    [x108, x30, x59, x87]
+}
+
+// 103 gates
+pub fn s6([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = !e;
+    let x1 = x0 ^ b;
+    let x2 = x0 & c;
+    let x3 = x1 ^ x2;
+    let x4 = e ^ c;
+    let x5 = x3 ^ x4;
+    let x6 = x5 & d;
+    let x7 = x3 ^ x6;
+    let x8 = !x1;
+    let x9 = e & b;
+    let x10 = x8 ^ x9;
+    let x11 = x10 & c;
+    let x12 = x8 ^ x11;
+    let x13 = !x9;
+    let x14 = x13 ^ x11;
+    let x15 = x12 ^ x14;
+    let x16 = x15 & d;
+    let x17 = x12 ^ x16;
+    let x18 = x7 ^ x17;
+    let x19 = x18 & a;
+    let x20 = x7 ^ x19;
+    let x21 = !x3;
+    let x22 = x0 | b;
+    let x23 = x22 ^ c;
+    let x24 = x21 ^ x23;
+    let x25 = x24 & d;
+    let x26 = x21 ^ x25;
+    let x27 = x1 ^ c;
+    let x28 = e ^ x1;
+    let x29 = x28 & c;
+    let x30 = e ^ x29;
+    let x31 = x27 ^ x30;
+    let x32 = x31 & d;
+    let x33 = x27 ^ x32;
+    let x34 = x26 ^ x33;
+    let x35 = x34 & a;
+    let x36 = x26 ^ x35;
+    let x37 = x20 ^ x36;
+    let x38 = x37 & f;
+    let x39 = x20 ^ x38;
+    let x40 = x13 & d;
+    let x41 = x11 ^ x40;
+    let x42 = x13 & c;
+    let x43 = x8 ^ x42;
+    let x44 = x43 ^ d;
+    let x45 = x41 ^ x44;
+    let x46 = x45 & a;
+    let x47 = x41 ^ x46;
+    let x48 = x22 & d;
+    let x49 = x14 ^ x48;
+    let x50 = e & c;
+    let x51 = x10 ^ x50;
+    let x52 = x1 ^ x50;
+    let x53 = x51 ^ x40;
+    let x54 = x49 ^ x53;
+    let x55 = x54 & a;
+    let x56 = x49 ^ x55;
+    let x57 = x47 ^ x56;
+    let x58 = x57 & f;
+    let x59 = x47 ^ x58;
+    let x60 = !x52;
+    let x61 = x30 ^ x60;
+    let x62 = x61 & d;
+    let x63 = x30 ^ x62;
+    let x64 = x1 & c;
+    let x65 = x0 ^ x64;
+    let x66 = x28 ^ x2;
+    let x67 = x65 ^ x66;
+    let x68 = x67 & d;
+    let x69 = x65 ^ x68;
+    let x70 = x63 ^ x69;
+    let x71 = x70 & a;
+    let x72 = x63 ^ x71;
+    let x73 = x10 & d;
+    let x74 = x30 ^ x73;
+    let x75 = !x66;
+    let x76 = e & d;
+    let x77 = x21 ^ x76;
+    let x78 = x74 ^ x77;
+    let x79 = x78 & a;
+    let x80 = x74 ^ x79;
+    let x81 = x72 ^ x80;
+    let x82 = x81 & f;
+    let x83 = x72 ^ x82;
+    let x84 = !x67;
+    let x85 = !x61;
+    let x86 = x84 ^ x85;
+    let x87 = x86 & d;
+    let x88 = x84 ^ x87;
+    let x89 = x86 & a;
+    let x90 = x88 ^ x89;
+    let x91 = x85 ^ x75;
+    let x92 = x91 & d;
+    let x93 = x85 ^ x92;
+    let x94 = x22 & c;
+    let x95 = b ^ x94;
+    let x96 = x95 ^ x73;
+    let x97 = x93 ^ x96;
+    let x98 = x97 & a;
+    let x99 = x93 ^ x98;
+    let x100 = x90 ^ x99;
+    let x101 = x100 & f;
+    let x102 = x90 ^ x101;
+    [x102, x39, x59, x83]
+}
+
+// 99 gates
+pub fn s7([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = e ^ b;
+    let x1 = b & d;
+    let x2 = e ^ x1;
+    let x3 = !x0;
+    let x4 = !b;
+    let x5 = e & d;
+    let x6 = x3 ^ x5;
+    let x7 = x2 ^ x6;
+    let x8 = x7 & c;
+    let x9 = x2 ^ x8;
+    let x10 = e | x4;
+    let x11 = b ^ x10;
+    let x12 = x11 & d;
+    let x13 = b ^ x12;
+    let x14 = !e;
+    let x15 = x14 & x4;
+    let x16 = x15 ^ x12;
+    let x17 = x13 ^ x16;
+    let x18 = x17 & c;
+    let x19 = x13 ^ x18;
+    let x20 = x9 ^ x19;
+    let x21 = x20 & a;
+    let x22 = x9 ^ x21;
+    let x23 = !x2;
+    let x24 = x23 ^ c;
+    let x25 = x17 & d;
+    let x26 = x0 ^ x25;
+    let x27 = !x10;
+    let x28 = x27 ^ x25;
+    let x29 = x26 ^ x28;
+    let x30 = x29 & c;
+    let x31 = x26 ^ x30;
+    let x32 = x24 ^ x31;
+    let x33 = x32 & a;
+    let x34 = x24 ^ x33;
+    let x35 = x22 ^ x34;
+    let x36 = x35 & f;
+    let x37 = x22 ^ x36;
+    let x38 = x4 & d;
+    let x39 = x3 ^ x38;
+    let x40 = b & c;
+    let x41 = x39 ^ x40;
+    let x42 = x41 ^ x9;
+    let x43 = x42 & a;
+    let x44 = x41 ^ x43;
+    let x45 = !x15;
+    let x46 = x10 & d;
+    let x47 = x14 ^ x46;
+    let x48 = x15 & d;
+    let x49 = x3 ^ x48;
+    let x50 = x47 ^ x49;
+    let x51 = x50 & c;
+    let x52 = x47 ^ x51;
+    let x53 = x0 ^ x1;
+    let x54 = x3 ^ x53;
+    let x55 = x54 & c;
+    let x56 = x3 ^ x55;
+    let x57 = x52 ^ x56;
+    let x58 = x57 & a;
+    let x59 = x52 ^ x58;
+    let x60 = x44 ^ x59;
+    let x61 = x60 & f;
+    let x62 = x44 ^ x61;
+    let x63 = x26 ^ c;
+    let x64 = x3 & d;
+    let x65 = b ^ x64;
+    let x66 = x45 & c;
+    let x67 = x65 ^ x66;
+    let x68 = x63 ^ x67;
+    let x69 = x68 & a;
+    let x70 = x63 ^ x69;
+    let x71 = b ^ d;
+    let x72 = x64 & c;
+    let x73 = x71 ^ x72;
+    let x74 = x4 ^ x46;
+    let x75 = x74 ^ c;
+    let x76 = x73 ^ x75;
+    let x77 = x76 & a;
+    let x78 = x73 ^ x77;
+    let x79 = x70 ^ x78;
+    let x80 = x79 & f;
+    let x81 = x70 ^ x80;
+    let x82 = !x6;
+    let x83 = x14 ^ d;
+    let x84 = x82 ^ x83;
+    let x85 = x84 & c;
+    let x86 = x82 ^ x85;
+    let x87 = x86 ^ a;
+    let x88 = x45 & d;
+    let x89 = x3 ^ x88;
+    let x90 = x89 ^ x85;
+    let x91 = !x16;
+    let x92 = x91 ^ c;
+    let x93 = x90 ^ x92;
+    let x94 = x93 & a;
+    let x95 = x90 ^ x94;
+    let x96 = x87 ^ x95;
+    let x97 = x96 & f;
+    let x98 = x87 ^ x97;
+    [x37, x62, x81, x98]
+}
+
+// 94 gates
+pub fn s8([a, b, c, d, e, f]: [u64; 6]) -> [u64; 4] {
+    let x0 = e ^ b;
+    let x1 = !b;
+    let x2 = x0 ^ x1;
+    let x3 = x2 & c;
+    let x4 = x0 ^ x3;
+    let x5 = !x0;
+    let x6 = b ^ x3;
+    let x7 = e & d;
+    let x8 = x4 ^ x7;
+    let x9 = e | x1;
+    let x10 = x9 ^ b;
+    let x11 = x10 & c;
+    let x12 = x9 ^ x11;
+    let x13 = x12 ^ d;
+    let x14 = x8 ^ x13;
+    let x15 = x14 & a;
+    let x16 = x8 ^ x15;
+    let x17 = e & x1;
+    let x18 = x5 & c;
+    let x19 = x17 ^ x18;
+    let x20 = x19 ^ x6;
+    let x21 = x20 & d;
+    let x22 = x19 ^ x21;
+    let x23 = x1 & c;
+    let x24 = x2 ^ x23;
+    let x25 = x1 ^ c;
+    let x26 = x24 ^ x25;
+    let x27 = x26 & d;
+    let x28 = x24 ^ x27;
+    let x29 = x22 ^ x28;
+    let x30 = x29 & a;
+    let x31 = x22 ^ x30;
+    let x32 = x16 ^ x31;
+    let x33 = x32 & f;
+    let x34 = x16 ^ x33;
+    let x35 = !x17;
+    let x36 = x35 ^ c;
+    let x37 = !x26;
+    let x38 = x36 ^ x37;
+    let x39 = x38 & d;
+    let x40 = x36 ^ x39;
+    let x41 = x17 ^ x3;
+    let x42 = x41 ^ x6;
+    let x43 = x42 & d;
+    let x44 = x41 ^ x43;
+    let x45 = x40 ^ x44;
+    let x46 = x45 & a;
+    let x47 = x40 ^ x46;
+    let x48 = x0 ^ c;
+    let x49 = x35 & d;
+    let x50 = x48 ^ x49;
+    let x51 = x42 & c;
+    let x52 = b ^ x51;
+    let x53 = x5 & d;
+    let x54 = x52 ^ x53;
+    let x55 = x50 ^ x54;
+    let x56 = x55 & a;
+    let x57 = x50 ^ x56;
+    let x58 = x47 ^ x57;
+    let x59 = x58 & f;
+    let x60 = x47 ^ x59;
+    let x61 = !x42;
+    let x62 = x0 & c;
+    let x63 = x61 ^ x62;
+    let x64 = x9 & d;
+    let x65 = x63 ^ x64;
+    let x66 = !x48;
+    let x67 = x23 & d;
+    let x68 = x66 ^ x67;
+    let x69 = x65 ^ x68;
+    let x70 = x69 & a;
+    let x71 = x65 ^ x70;
+    let x72 = !x65;
+    let x73 = x6 ^ d;
+    let x74 = x72 ^ x73;
+    let x75 = x74 & a;
+    let x76 = x72 ^ x75;
+    let x77 = x71 ^ x76;
+    let x78 = x77 & f;
+    let x79 = x71 ^ x78;
+    let x80 = !x57;
+    let x81 = x9 ^ x18;
+    let x82 = x41 & d;
+    let x83 = x81 ^ x82;
+    let x84 = e & c;
+    let x85 = x0 ^ x84;
+    let x86 = x62 & d;
+    let x87 = x85 ^ x86;
+    let x88 = x83 ^ x87;
+    let x89 = x88 & a;
+    let x90 = x83 ^ x89;
+    let x91 = x80 ^ x90;
+    let x92 = x91 & f;
+    let x93 = x80 ^ x92;
+    [x60, x79, x34, x93]
+}
+
+#[test]
+// every circuit must agree with the FIPS tables on all the 64 inputs
+fn test_sbox_circuits() {
+    use crate::ciphers::des::des_utils::query_s_box;
+
+    let circuits = [s1, s2, s3, s4, s5, s6, s7, s8];
+    for (s, circuit) in circuits.iter().enumerate() {
+        // word j holds the input bit j of every one of the 64 inputs (lane x is input x)
+        let input: [u64; 6] = std::array::from_fn(|j| {
+            (0..64u64).fold(0, |acc, x| acc | ((x >> (5 - j)) & 0x1) << x)
+        });
+        let output = circuit(input);
+        for x in 0..64 {
+            let value = output.iter().fold(0, |acc, word| (acc << 1) | ((word >> x) & 0x1) as u8);
+            assert_eq!(value, query_s_box(s, x as u8), "S{} input {}", s + 1, x);
+        }
+    }
+}
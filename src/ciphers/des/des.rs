@@ -4,17 +4,32 @@
 * NOTE to interpret the tables below (IP, E, S_BOX)
 * - in FIPS 46-3 bits are 1-indexed
 * - bit 1 is the most significant bit
+*
+* NOTE two interchangeable backends compute the same function:
+* - Reference: follows the FIPS tables bit by bit (des_utils)
+* - Table: merged SP tables and delta swap IP/FP (des_table), much faster
 */
 
-use std::u64;
-
-use cryptography_playground::BlockCipher;
+use crate::BlockCipher;
 use crate::ciphers::des::des_constants::*;
 use crate::ciphers::des::des_utils::*;
+use crate::ciphers::des::des_table;
+
+// implementation used to encrypt and decrypt blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DESBackend {
+    #[default]
+    Reference,
+    Table,
+}
 
 pub struct DES {
-    // currently used key
-    key: u64
+    // round keys of the currently used key, expanded once at new/change_key
+    encryption_keys: [U48; FEISTEL_ROUNDS],
+    // same round keys in reverse order
+    decryption_keys: [U48; FEISTEL_ROUNDS],
+    // implementation in use
+    backend: DESBackend,
 }
 
 impl BlockCipher for DES {
@@ -30,27 +45,43 @@ impl BlockCipher for DES {
 }
 
 impl DES {
-    // initialize the cipher
+    // initialize the cipher (reference backend)
     pub fn new(key: u64) -> Self {
-        Self { key }
+        Self::with_backend(key, DESBackend::Reference)
     }
 
-    // change the secret key to use
+    // initialize the cipher using the given backend
+    pub fn with_backend(key: u64, backend: DESBackend) -> Self {
+        let encryption_keys = Self::schedule_subkeys(key);
+        let mut decryption_keys = encryption_keys;
+        decryption_keys.reverse();
+        Self { encryption_keys, decryption_keys, backend }
+    }
+
+    // change the secret key to use, keeping the backend
     pub fn change_key(&mut self, new_key: u64) {
-        self.key = new_key;
+        *self = Self::with_backend(new_key, self.backend);
+    }
+
+    // backend in use
+    pub fn backend(&self) -> DESBackend {
+        self.backend
     }
 
     // encrypts a single bloc
     pub fn des_encrypt_block(&self, p: u64) -> u64 {
-        let round_keys = self.schedule_subkeys();
-        self.run_feistel_network(p, &round_keys)
+        match self.backend {
+            DESBackend::Reference => self.run_feistel_network(p, &self.encryption_keys),
+            DESBackend::Table => des_table::run_feistel_network(p, &self.encryption_keys),
+        }
     }
 
     // decrypts a single block
     pub fn des_decrypt_block(&self, c: u64) -> u64 {
-        let round_keys = self.schedule_subkeys();
-        let reversed_keys: Vec<U48> = round_keys.iter().rev().copied().collect();
-        self.run_feistel_network(c, &reversed_keys)
+        match self.backend {
+            DESBackend::Reference => self.run_feistel_network(c, &self.decryption_keys),
+            DESBackend::Table => des_table::run_feistel_network(c, &self.decryption_keys),
+        }
     }
     
     fn run_feistel_network(&self, block: u64, round_keys: &[U48]) -> u64 {
@@ -58,8 +89,8 @@ impl DES {
         let (mut l, mut r) = split_block(apply_initial_permutation(block, false));
 
         // 2) apply 16 rounds
-        for i in 0..FEISTEL_ROUNDS {
-            let tmp = l ^ self.round_function(expand_r(r), round_keys[i]);
+        for &round_key in round_keys.iter().take(FEISTEL_ROUNDS) {
+            let tmp = l ^ self.round_function(expand_r(r), round_key);
             l = r;
             r = tmp;
         }
@@ -92,11 +123,11 @@ impl DES {
     }
 
     // key scheduler
-    fn schedule_subkeys(&self) -> [U48; FEISTEL_ROUNDS] {
+    fn schedule_subkeys(key: u64) -> [U48; FEISTEL_ROUNDS] {
         let mut round_keys = [0u64; 16];
 
-        // 1. apply PC-1 to convert self.key into a 56 bit key
-        let key = apply_pc1(self.key);
+        // 1. apply PC-1 to convert the key into a 56 bit key
+        let key = apply_pc1(key);
 
         // 2. split the key into two 28 bit halves
         let (mut c, mut d) = split_key(key);
@@ -138,3 +169,41 @@ fn test_know_des_vector() {
         "Known DES vector failed decryption"
     );
 }
+
+#[test]
+// the cached schedules must follow change_key
+fn test_des_change_key() {
+    let mut cipher = DES::new(0);
+    let stale = cipher.encrypt_block(0x0123456789ABCDEF);
+
+    cipher.change_key(0x133457799BBCDFF1);
+    assert_eq!(cipher.encrypt_block(0x0123456789ABCDEF), 0x85E813540F0AB405);
+    assert_eq!(cipher.decrypt_block(0x85E813540F0AB405), 0x0123456789ABCDEF);
+
+    cipher.change_key(0);
+    assert_eq!(cipher.encrypt_block(0x0123456789ABCDEF), stale);
+}
+
+#[test]
+// both backends must compute the same permutation
+fn test_des_backends_agree() {
+    let mut key = 0x133457799BBCDFF1u64;
+    let mut block = 0x0123456789ABCDEFu64;
+
+    let table = DES::with_backend(key, DESBackend::Table);
+    assert_eq!(table.backend(), DESBackend::Table);
+    assert_eq!(table.encrypt_block(block), 0x85E813540F0AB405);
+    assert_eq!(table.decrypt_block(0x85E813540F0AB405), block);
+
+    for _ in 0..200 {
+        let reference = DES::new(key);
+        let table = DES::with_backend(key, DESBackend::Table);
+        let ciphertext = reference.encrypt_block(block);
+        assert_eq!(table.encrypt_block(block), ciphertext);
+        assert_eq!(table.decrypt_block(ciphertext), block);
+        assert_eq!(table.decrypt_block(block), reference.decrypt_block(block));
+
+        key = key.wrapping_mul(0x9E3779B97F4A7C15).wrapping_add(ciphertext);
+        block = block.rotate_left(7) ^ ciphertext;
+    }
+}
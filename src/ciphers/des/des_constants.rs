@@ -18,7 +18,7 @@ pub const IP: [u8; 64] = [
 ];
 
 // the inverse of the initial permutation table
-pub const IPinverse: [u8; 64] = [
+pub const IP_INVERSE: [u8; 64] = [
     40, 8, 48, 16, 56, 24, 64, 32,
     39, 7, 47, 15, 55, 23, 63, 31,
     38, 6, 46, 14, 54, 22, 62, 30,
@@ -0,0 +1,132 @@
+/*
+* Table driven DES round function and permutations
+*
+* Carachteristics:
+* - the 8 S-boxes and the P permutation are merged into 8 tables of 64 u32 (SP[i][x] = P(S_i(x)))
+*   so a round is 8 lookups and XORs instead of walking the FIPS tables bit by bit
+* - the E expansion is never materialized: the 6 bits entering S-box i are
+*   bits 4i..4i+5 of R (cyclic), extracted with a single rotation
+* - IP and IP^-1 are computed with 5 delta swaps each
+*
+* NOTE the SP tables are built at compile time from the FIPS tables in des_constants
+*/
+
+use crate::ciphers::des::des_constants::*;
+use crate::ciphers::des::des_utils::query_s_box;
+
+// SP[i][x] = P(S_i(x) placed in its 4 bit slot), indexed like extract_sbox_input
+pub(super) const SP: [[u32; 64]; 8] = build_sp_tables();
+
+const fn build_sp_tables() -> [[u32; 64]; 8] {
+    let mut tables = [[0u32; 64]; 8];
+    let mut i = 0;
+    while i < 8 {
+        let mut x = 0;
+        while x < 64 {
+            let out = (query_s_box(i, x as u8) as u32) << (28 - 4 * i);
+
+            // apply P to the S-box output
+            let mut permuted = 0u32;
+            let mut j = 0;
+            while j < 32 {
+                permuted |= ((out >> (32 - P[j] as u32)) & 0x1) << (31 - j);
+                j += 1;
+            }
+            tables[i][x] = permuted;
+            x += 1;
+        }
+        i += 1;
+    }
+    tables
+}
+
+// exchanges the bits of a selected by mask with the bits of b selected by mask << shift
+#[inline(always)]
+fn delta_swap(a: &mut u32, b: &mut u32, shift: u32, mask: u32) {
+    let t = ((*a >> shift) ^ *b) & mask;
+    *b ^= t;
+    *a ^= t << shift;
+}
+
+// initial permutation, returns the two halves (L0, R0)
+pub(super) fn initial_permutation(block: u64) -> (u32, u32) {
+    let (mut l, mut r) = ((block >> 32) as u32, block as u32);
+    delta_swap(&mut l, &mut r, 4, 0x0f0f0f0f);
+    delta_swap(&mut l, &mut r, 16, 0x0000ffff);
+    delta_swap(&mut r, &mut l, 2, 0x33333333);
+    delta_swap(&mut r, &mut l, 8, 0x00ff00ff);
+    delta_swap(&mut l, &mut r, 1, 0x55555555);
+    (l, r)
+}
+
+// inverse of the initial permutation: the same swaps in reverse order
+pub(super) fn final_permutation(mut l: u32, mut r: u32) -> u64 {
+    delta_swap(&mut l, &mut r, 1, 0x55555555);
+    delta_swap(&mut r, &mut l, 8, 0x00ff00ff);
+    delta_swap(&mut r, &mut l, 2, 0x33333333);
+    delta_swap(&mut l, &mut r, 16, 0x0000ffff);
+    delta_swap(&mut l, &mut r, 4, 0x0f0f0f0f);
+    ((l as u64) << 32) | (r as u64)
+}
+
+// round function: E, XOR with the round key, S-boxes and P
+#[inline(always)]
+fn round_function(r: u32, round_key: U48) -> u32 {
+    let mut out = 0u32;
+    for (i, table) in SP.iter().enumerate() {
+        // bits 4i..4i+5 (1-indexed, cyclic) of r are the 6 E output bits for S-box i
+        let e = r.rotate_left(4 * i as u32 + 5);
+        let k = (round_key >> (42 - 6 * i)) as u32;
+        out ^= table[((e ^ k) & 0x3f) as usize];
+    }
+    out
+}
+
+// 16 rounds with the given round keys (reversed for decryption)
+pub fn run_feistel_network(block: u64, round_keys: &[U48; FEISTEL_ROUNDS]) -> u64 {
+    let (mut l, mut r) = initial_permutation(block);
+    for &round_key in round_keys {
+        let tmp = l ^ round_function(r, round_key);
+        l = r;
+        r = tmp;
+    }
+    final_permutation(r, l)
+}
+
+#[test]
+// the delta swaps must match the FIPS IP and IP^-1 tables
+fn test_fast_permutations_match_reference() {
+    use crate::ciphers::des::des_utils::{apply_initial_permutation, split_block};
+
+    let mut x = 0x0123456789ABCDEFu64;
+    for _ in 0..1000 {
+        assert_eq!(initial_permutation(x), split_block(apply_initial_permutation(x, false)));
+        let (l, r) = split_block(x);
+        assert_eq!(final_permutation(l, r), apply_initial_permutation(x, true));
+        x = x.wrapping_mul(0x9E3779B97F4A7C15).wrapping_add(1);
+    }
+}
+
+#[test]
+// every SP entry must equal the reference S-box lookup followed by P
+fn test_sp_tables_match_reference() {
+    use crate::ciphers::des::des_utils::{apply_p, pad_sbox_output, query_s_box};
+
+    for (i, table) in SP.iter().enumerate() {
+        for (x, &entry) in table.iter().enumerate() {
+            assert_eq!(entry, apply_p(pad_sbox_output(i, query_s_box(i, x as u8))), "SP[{}][{}]", i, x);
+        }
+    }
+}
+
+#[test]
+// the rotation trick must select the same bits as the E table
+fn test_round_function_expansion() {
+    use crate::ciphers::des::des_utils::{expand_r, extract_sbox_input};
+
+    let r: u32 = 0b11110000101010101111000010101010;
+    let expanded = expand_r(r);
+    for i in 0..8 {
+        assert_eq!(r.rotate_left(4 * i as u32 + 5) & 0x3f, extract_sbox_input(i, expanded) as u32);
+    }
+}
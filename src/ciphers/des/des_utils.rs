@@ -3,7 +3,7 @@ use crate::ciphers::des::des_constants::*;
 // takes a 64 bit DES block and permutates it using IP or IP^-1 if inverse if false
 pub fn apply_initial_permutation(input: u64, inverse: bool) -> u64 {
     // select which IP table to use (IP or IP^-1) based on the value of inverse
-    let ip_table = if inverse { IPinverse } else { IP };
+    let ip_table = if inverse { IP_INVERSE } else { IP };
     // output
     let mut out = 0u64;
     // loop through the chosen IP table
@@ -71,7 +71,7 @@ pub fn expand_r(r: u32) -> U48 {
 }
 
 // takes a 6 bit input (6 LSB of the u8) and returns a 4 bit output
-pub fn query_s_box(idx: usize, input: u8) -> u8 {
+pub const fn query_s_box(idx: usize, input: u8) -> u8 {
     // row is byte 5th and 0th
     let row = ((input & 0x20) >> 4) | (input & 0x1);
     // col are bytes 4th, ..., 1th
@@ -1,6 +1,14 @@
 // exposed to other modules outside des/
 pub mod des;
+pub mod bitsliced_des;
+pub mod triple_des;
 
 // local to des/ module
+mod bitsliced_sboxes;
 mod des_constants;
+mod des_table;
 mod des_utils;
+
+pub use des::{DES, DESBackend};
+pub use bitsliced_des::{BitslicedDES, BitslicedKeySearch};
+pub use triple_des::TripleDES;
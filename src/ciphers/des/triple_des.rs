@@ -0,0 +1,152 @@
+/*
+* Triple DES (TDEA) in the EDE form as defined by NIST SP 800-67
+*
+* C = E_K3(D_K2(E_K1(P)))
+* P = D_K1(E_K2(D_K3(C)))
+*
+* Keying options:
+* - option 1: K1, K2 and K3 are independent (three-key 3DES, 168 bit key)
+* - option 2: K1 and K2 are independent and K3 = K1 (two-key 3DES, 112 bit key)
+* - option 3: K1 = K2 = K3, equivalent to single DES (backward compatibility only)
+*/
+
+use crate::{BlockType, BlockCipher, Error, Result};
+use crate::ciphers::des::des::{DES, DESBackend};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyingOption {
+    Option1,
+    Option2,
+    Option3,
+}
+
+pub struct TripleDES {
+    // one DES instance for each of the three keys
+    k1: DES,
+    k2: DES,
+    k3: DES,
+    // keying option derived from the keys
+    option: KeyingOption,
+}
+
+impl BlockCipher for TripleDES {
+    type Block = u64;
+
+    fn encrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.tdes_encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: Self::Block) -> Self::Block {
+        self.tdes_decrypt_block(block)
+    }
+}
+
+impl TripleDES {
+    // initialize the cipher with three keys
+    // the keying option is derived from which of them are equal
+    pub fn new(k1: u64, k2: u64, k3: u64) -> Self {
+        Self::with_backend(k1, k2, k3, DESBackend::Reference)
+    }
+
+    // same as new, with the given DES backend for the three instances
+    pub fn with_backend(k1: u64, k2: u64, k3: u64, backend: DESBackend) -> Self {
+        let option = if k1 == k2 && k2 == k3 {
+            KeyingOption::Option3
+        } else if k1 == k3 {
+            KeyingOption::Option2
+        } else {
+            KeyingOption::Option1
+        };
+
+        Self {
+            k1: DES::with_backend(k1, backend),
+            k2: DES::with_backend(k2, backend),
+            k3: DES::with_backend(k3, backend),
+            option,
+        }
+    }
+
+    // initialize the cipher using keying option 2 (K3 = K1)
+    pub fn new_two_key(k1: u64, k2: u64) -> Self {
+        Self::new(k1, k2, k1)
+    }
+
+    // initialize the cipher from a 16 (option 2) or 24 (option 1) bytes key bundle
+    pub fn from_key_bundle(bundle: &[u8]) -> Result<Self> {
+        let keys: Vec<u64> = bundle
+            .chunks(8)
+            .map(u64::from_bytes)
+            .collect::<Result<_>>()
+            .map_err(|_| Error::InvalidKeyLength(bundle.len()))?;
+
+        match keys[..] {
+            [k1, k2] => Ok(Self::new_two_key(k1, k2)),
+            [k1, k2, k3] => Ok(Self::new(k1, k2, k3)),
+            _ => Err(Error::InvalidKeyLength(bundle.len())),
+        }
+    }
+
+    // keying option of the keys in use
+    pub fn keying_option(&self) -> KeyingOption {
+        self.option
+    }
+
+    // encrypts a single block (encrypt-decrypt-encrypt)
+    pub fn tdes_encrypt_block(&self, p: u64) -> u64 {
+        let tmp = self.k1.des_encrypt_block(p);
+        let tmp = self.k2.des_decrypt_block(tmp);
+        self.k3.des_encrypt_block(tmp)
+    }
+
+    // decrypts a single block (decrypt-encrypt-decrypt)
+    pub fn tdes_decrypt_block(&self, c: u64) -> u64 {
+        let tmp = self.k3.des_decrypt_block(c);
+        let tmp = self.k2.des_encrypt_block(tmp);
+        self.k1.des_decrypt_block(tmp)
+    }
+}
+
+#[test]
+// NIST SP 800-67 Rev. 1 Appendix B (keying option 1)
+fn test_known_tdes_vector_three_keys() {
+    use crate::{CipherOperationMode, ECB};
+
+    let plaintext = b"The qufck brown fox jump";
+    let expected: [u64; 3] = [0xA826FD8CE53B855F, 0xCCE21C8112256FE6, 0x68D5C05DD9B6B900];
+    let expected: Vec<u8> = expected.iter().flat_map(|b| b.to_be_bytes()).collect();
+
+    for backend in [DESBackend::Reference, DESBackend::Table] {
+        let cipher = TripleDES::with_backend(0x0123456789ABCDEF, 0x23456789ABCDEF01, 0x456789ABCDEF0123, backend);
+        assert_eq!(cipher.keying_option(), KeyingOption::Option1);
+
+        let ciphertext = ECB.encrypt(&cipher, plaintext).unwrap();
+        assert_eq!(ciphertext, expected, "Known 3DES vector failed encryption ({:?})", backend);
+        assert_eq!(ECB.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "Known 3DES vector failed decryption ({:?})", backend);
+    }
+}
+
+#[test]
+fn test_known_tdes_vector_two_keys() {
+    let bundle = 0x0123456789ABCDEFFEDCBA9876543210u128.to_be_bytes();
+    let cipher = TripleDES::from_key_bundle(&bundle).unwrap();
+    assert_eq!(cipher.keying_option(), KeyingOption::Option2);
+
+    let plaintext: u64 = 0x0123456789ABCDEF;
+    let expected: u64 = 0x1A4D672DCA6CB335;
+    let ciphertext = cipher.encrypt_block(plaintext);
+    assert_eq!(ciphertext, expected, "Known 2-key 3DES vector failed encryption");
+    assert_eq!(cipher.decrypt_block(ciphertext), plaintext, "Known 2-key 3DES vector failed decryption");
+
+    // only 16 and 24 bytes bundles are valid
+    assert_eq!(TripleDES::from_key_bundle(&bundle[..12]).err(), Some(Error::InvalidKeyLength(12)));
+    assert_eq!(TripleDES::from_key_bundle(&[0u8; 8]).err(), Some(Error::InvalidKeyLength(8)));
+}
+
+#[test]
+// keying option 3 must be equivalent to single DES
+fn test_tdes_option3_is_des() {
+    let key: u64 = 0x133457799BBCDFF1;
+    let cipher = TripleDES::new(key, key, key);
+    assert_eq!(cipher.keying_option(), KeyingOption::Option3);
+    assert_eq!(cipher.encrypt_block(0x0123456789ABCDEF), 0x85E813540F0AB405);
+}
@@ -1,5 +1,7 @@
 use num_bigint::BigUint;
-use num_integer::{gcd, ExtendedGcd, Integer};
+use num_integer::{ExtendedGcd, Integer};
+
+use crate::{Error, Result};
 
 pub struct ElGamal {
     p: u64,
@@ -9,20 +11,32 @@ pub struct ElGamal {
 }
 
 impl ElGamal {
-    pub fn new(p: u64, g: u64, x: u64) -> Self {
-        Self { p, g, x, h: mod_pow_bigint(g, x, p) } 
+    // fails if p can't be a modulus (p < 2)
+    pub fn new(p: u64, g: u64, x: u64) -> Result<Self> {
+        if p < 2 {
+            return Err(Error::InvalidParameter("ElGamal modulus must be at least 2"));
+        }
+        Ok(Self { p, g, x, h: mod_pow_bigint(g, x, p) })
     }
 
-    pub fn encrypt_message(&self, m: u64, k: u64) -> (u64, u64) {
+    // fails if m isn't smaller than p
+    pub fn encrypt_message(&self, m: u64, k: u64) -> Result<(u64, u64)> {
+        if m >= self.p {
+            return Err(Error::MessageOutOfRange);
+        }
         let c1 = mod_pow_bigint(self.g, k, self.p);
-        let c2 = (m * mod_pow_bigint(self.h, k, self.p)) % self.p;
-        (c1, c2)
+        let c2 = mod_mul(m, mod_pow_bigint(self.h, k, self.p), self.p);
+        Ok((c1, c2))
     }
 
-    pub fn decrypt_message(&self, c1: u64, c2: u64) -> u64 {
+    // fails if the ciphertext is malformed (c1 has no inverse mod p)
+    pub fn decrypt_message(&self, c1: u64, c2: u64) -> Result<u64> {
+        if c1 >= self.p || c2 >= self.p {
+            return Err(Error::MessageOutOfRange);
+        }
         let z = mod_pow_bigint(c1, self.x, self.p);
-        let z_inverse = mod_inverse(z, self.p).unwrap();
-        (c2 * z_inverse) % self.p
+        let z_inverse = mod_inverse(z, self.p).ok_or(Error::NoModularInverse)?;
+        Ok(mod_mul(c2, z_inverse, self.p))
     }
 }
 
@@ -31,7 +45,8 @@ fn mod_inverse(a: u64, n: u64) -> Option<u64> {
     // we use the extended_gcd() from the num_integer crate to find
     // the gcd and the Bézeut coefficients between a and n
     // Bézeut coefficients => a·x + n·y = gcd(a, n)
-    let ExtendedGcd { gcd, x, y: _ } = (a as i64).extended_gcd(&(n as i64));
+    // NOTE: i128 holds any u64 (and the coefficients, |x| <= n) without overflowing
+    let ExtendedGcd { gcd, x, y: _ } = (a as i128).extended_gcd(&(n as i128));
     
     // if the GCD(a,n) != than a has no inverse mod n
     if gcd != 1 {
@@ -39,7 +54,7 @@ fn mod_inverse(a: u64, n: u64) -> Option<u64> {
     } else {
         // Bézeut coefficients => a·x + n·y = gcd(a, n)
         // such that x is the inverse of a mod n
-        let n = n as i64;
+        let n = n as i128;
         Some(((x % n + n) % n) as u64)
     }
 }
@@ -56,19 +71,47 @@ fn mod_pow_bigint(base: u64, exp: u64, n: u64) -> u64 {
     let res = base.modpow(&exp, &n);
     
     // convert the result back to u64
-    res.try_into().unwrap()
+    // NOTE: res < n so it always fits in a single u64 digit
+    res.iter_u64_digits().next().unwrap_or(0)
+}
+
+// performs a * b mod n without overflowing
+fn mod_mul(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 * b as u128) % n as u128) as u64
 }
 
 #[test]
 fn test_el_gamal_basic() {
     let message: u64 = 5;
-    let cipher = ElGamal::new(19, 3, 5);
+    let cipher = ElGamal::new(19, 3, 5).unwrap();
     println!("PUB KEY: (h: {}, g: {}, p: {})", cipher.h, cipher.g, cipher.p);
     
-    let (c1, c2) = cipher.encrypt_message(message, 3);
+    let (c1, c2) = cipher.encrypt_message(message, 3).unwrap();
     println!("Ciphertext: ({}, {})", c1, c2);
 
-    let decrypted = cipher.decrypt_message(c1, c2);
+    let decrypted = cipher.decrypt_message(c1, c2).unwrap();
     println!("Decrypted: {}", decrypted);
     assert_eq!(message, decrypted);
+
+    // malformed ciphertexts are rejected instead of panicking
+    assert_eq!(cipher.decrypt_message(0, c2), Err(Error::NoModularInverse));
+    assert_eq!(cipher.decrypt_message(c1, 19), Err(Error::MessageOutOfRange));
+}
+
+#[test]
+// bad keys are rejected and moduli above i64::MAX work
+fn test_el_gamal_key_validation() {
+    assert!(matches!(ElGamal::new(0, 3, 5), Err(Error::InvalidParameter(_))));
+    assert!(matches!(ElGamal::new(1, 3, 5), Err(Error::InvalidParameter(_))));
+
+    // largest primes below 2^63 and 2^64
+    for p in [9223372036854775783u64, 18446744073709551557] {
+        let cipher = ElGamal::new(p, 5, 0x0123456789ABCDEF).unwrap();
+        let message = p - 2;
+        let (c1, c2) = cipher.encrypt_message(message, 0xFEDCBA9876543210).unwrap();
+        assert_eq!(cipher.decrypt_message(c1, c2).unwrap(), message);
+
+        let inverse = mod_inverse(message, p).unwrap();
+        assert_eq!(mod_mul(message, inverse, p), 1);
+    }
 }
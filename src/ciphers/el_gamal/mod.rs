@@ -1 +1,3 @@
 pub mod el_gamal;
+
+pub use el_gamal::ElGamal;
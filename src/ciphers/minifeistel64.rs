@@ -12,13 +12,13 @@
 *   3. s[i] = s[i+2] (right rotation by 2 places)
 */
 
-use cryptography_playground::BlockCipher;
+use crate::BlockCipher;
 
 const FEISTEL_ROUNDS: usize = 8;
 
 pub struct MiniFeistel64 {
-    // currently used key
-    key: u64    
+    // round keys of the currently used key, derived once at new/change_key
+    round_keys: [u32; FEISTEL_ROUNDS]
 }
 
 impl BlockCipher for MiniFeistel64 {
@@ -36,28 +36,25 @@ impl BlockCipher for MiniFeistel64 {
 impl MiniFeistel64 {
     // initialize the cipher
     pub fn new(key: u64) -> Self {
-        Self { key }
+        Self { round_keys: Self::derive_round_keys(key) }
     }
     
     // changes the secret key to use
     pub fn change_key(&mut self, new_key: u64) {
-        self.key = new_key;
+        self.round_keys = Self::derive_round_keys(new_key);
     }
 
     pub fn feistel_encrypt_block(&self, p: u64) -> u64 {
         // split p into L and R
         let (mut old_l, mut old_r) = self.split_block(p);
 
-        // compute round keys
-        let round_keys = self.derive_round_keys();
-        
         // for every round
-        for round_number in 0..FEISTEL_ROUNDS {
+        for &round_key in &self.round_keys {
             // compute this round;
             let new_l = old_r;
             let new_r = old_l ^ self.round_function(
                 old_r, 
-                round_keys[round_number]
+                round_key
             );
 
             // update old values for next iteration
@@ -73,15 +70,12 @@ impl MiniFeistel64 {
         // split c into L and R
         let (mut old_r, mut old_l) = self.split_block(c);
         
-        // compute round keys
-        let round_keys = self.derive_round_keys();
-        
         for round_number in (0..FEISTEL_ROUNDS).rev() {
             // compute this round
             let new_r = old_l;
             let new_l = old_r ^ self.round_function(
                 old_l,
-                round_keys[round_number]
+                self.round_keys[round_number]
             );
             
             // update old values for next iteration
@@ -101,15 +95,15 @@ impl MiniFeistel64 {
 
         // phase 1 (substitution) -> s[i] XOR k[i]
         //
-        for i in 0..4 {
-            s[i] ^= k[i];
+        for (s_i, k_i) in s.iter_mut().zip(k) {
+            *s_i ^= k_i;
         }
 
         // phase 2 (substitution) -> left shift every bit by 1
         // rotate_left() makes sure we don't lose any bit due to shifting
         // by appending any "cut out" bit
-        for i in 0..4 {
-            s[i] = s[i].rotate_left(1);
+        for s_i in s.iter_mut() {
+            *s_i = s_i.rotate_left(1);
         }
 
         // phase 3 (permutation) -> shift every chunk by 2 positions to the right
@@ -124,15 +118,15 @@ impl MiniFeistel64 {
     }
     
     // compute the round keys for all rounds
-    fn derive_round_keys(&self) -> [u32; FEISTEL_ROUNDS] {
+    fn derive_round_keys(key: u64) -> [u32; FEISTEL_ROUNDS] {
         let mut keys = [0u32; FEISTEL_ROUNDS];
         
         // super simle key derivation function that does:
         // 1) key << (round_number * 8)
         // 2) take the 32th most significant bits out
-        for i in 0..FEISTEL_ROUNDS {
-            let rotated = self.key.rotate_left((i * 8) as u32);
-            keys[i] = (rotated >> 32) as u32
+        for (i, round_key) in keys.iter_mut().enumerate() {
+            let rotated = key.rotate_left((i * 8) as u32);
+            *round_key = (rotated >> 32) as u32
         }
 
         keys
@@ -1,4 +1,15 @@
 pub mod minifeistel64;
 pub mod des;
+pub mod aes;
 pub mod rsa;
 pub mod el_gamal;
+pub mod chacha20;
+pub mod salsa20;
+
+pub use minifeistel64::MiniFeistel64;
+pub use des::{DES, TripleDES, BitslicedDES, BitslicedKeySearch};
+pub use aes::AES;
+pub use rsa::RSA;
+pub use el_gamal::ElGamal;
+pub use chacha20::{ChaCha20, XChaCha20};
+pub use salsa20::{Salsa20, XSalsa20};
@@ -1 +1,13 @@
+// exposed to other modules outside rsa/
 pub mod rsa;
+pub mod oaep;
+pub mod pkcs1v15;
+pub mod signature;
+
+// local to rsa/ module
+pub(crate) mod rsa_utils;
+
+pub use rsa::RSA;
+pub use oaep::OAEP;
+pub use pkcs1v15::PKCS1v15Encrypt;
+pub use signature::{PKCS1v15Sign, PSS};
@@ -0,0 +1,190 @@
+/*
+* RSAES-OAEP encryption scheme (RFC 8017 section 7.1, PKCS#1 v2.2)
+*
+* the message is encoded with a random seed before textbook RSA:
+*   DB = lHash || PS || 0x01 || M              (lHash = H(label), PS = zeros)
+*   maskedDB = DB XOR MGF(seed, k - hLen - 1)
+*   maskedSeed = seed XOR MGF(maskedDB, hLen)
+*   EM = 0x00 || maskedSeed || maskedDB
+* making encryption probabilistic and the ciphertext non malleable
+*
+* decoding failures all return the same Error::DecryptionFailed
+* and the checks are performed without early exits (Manger's attack)
+*/
+
+use std::marker::PhantomData;
+
+use rand::RngCore;
+
+use crate::{Error, Result};
+use crate::ciphers::rsa::rsa::RSA;
+use crate::ciphers::rsa::rsa_utils::{i2osp, os2ip};
+use crate::hash::{Hash, mgf1};
+use crate::utils::ct_eq;
+
+// H: hash used for the label, G: hash used by MGF1 (usually the same)
+pub struct OAEP<H: Hash, G: Hash = H> {
+    // optional label associated with the message
+    pub label: Vec<u8>,
+    hash: PhantomData<(H, G)>,
+}
+
+impl<H: Hash, G: Hash> OAEP<H, G> {
+    pub fn new(label: &[u8]) -> Self {
+        Self { label: label.to_vec(), hash: PhantomData }
+    }
+
+    // maximum message length in bytes for the given key
+    pub fn max_message_len(&self, key: &RSA) -> usize {
+        key.modulus_len().saturating_sub(2 * H::OUTPUT_SIZE + 2)
+    }
+
+    // encrypts message with a fresh random seed
+    pub fn encrypt(&self, key: &RSA, message: &[u8]) -> Result<Vec<u8>> {
+        let mut seed = vec![0u8; H::OUTPUT_SIZE];
+        rand::rng().fill_bytes(&mut seed);
+        self.encrypt_with_seed(key, message, &seed)
+    }
+
+    // encrypts message using the provided seed (deterministic, for test vectors)
+    fn encrypt_with_seed(&self, key: &RSA, message: &[u8], seed: &[u8]) -> Result<Vec<u8>> {
+        let k = key.modulus_len();
+        let h_len = H::OUTPUT_SIZE;
+
+        // 1. length checking
+        if k < 2 * h_len + 2 || message.len() > k - 2 * h_len - 2 {
+            return Err(Error::MessageTooLong);
+        }
+
+        // 2. DB = lHash || PS || 0x01 || M
+        let mut db = H::digest(&self.label);
+        db.resize(k - message.len() - h_len - 2, 0);
+        db.push(0x01);
+        db.extend_from_slice(message);
+
+        // 3. mask DB with the seed and the seed with the masked DB
+        xor_in_place(&mut db, &mgf1::<G>(seed, k - h_len - 1));
+        let mut masked_seed = seed.to_vec();
+        xor_in_place(&mut masked_seed, &mgf1::<G>(&db, h_len));
+
+        // 4. EM = 0x00 || maskedSeed || maskedDB
+        let mut em = vec![0x00];
+        em.extend(masked_seed);
+        em.extend(db);
+
+        // 5. textbook RSA on the encoded message
+        let c = key.encrypt_message(&os2ip(&em))?;
+        i2osp(&c, k)
+    }
+
+    // decrypts and decodes ciphertext
+    // every failure is reported as Error::DecryptionFailed
+    pub fn decrypt(&self, key: &RSA, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let k = key.modulus_len();
+        let h_len = H::OUTPUT_SIZE;
+
+        // 1. length checking
+        if ciphertext.len() != k || k < 2 * h_len + 2 {
+            return Err(Error::DecryptionFailed);
+        }
+
+        // 2. textbook RSA decryption
+        let m = key
+            .decrypt_message(&os2ip(ciphertext))
+            .map_err(|_| Error::DecryptionFailed)?;
+        let em = i2osp(&m, k).map_err(|_| Error::DecryptionFailed)?;
+
+        // 3. unmask seed and DB
+        let (y, rest) = em.split_at(1);
+        let (masked_seed, masked_db) = rest.split_at(h_len);
+        let mut seed = masked_seed.to_vec();
+        xor_in_place(&mut seed, &mgf1::<G>(masked_db, h_len));
+        let mut db = masked_db.to_vec();
+        xor_in_place(&mut db, &mgf1::<G>(&seed, k - h_len - 1));
+
+        // 4. check Y = 0, lHash' = lHash and PS || 0x01
+        // NOTE: & instead of && so that every check is always evaluated
+        let mut valid = (y[0] == 0) & ct_eq(&db[..h_len], &H::digest(&self.label));
+        let mut looking_for_one = true;
+        let mut separator = 0usize;
+        for (i, &b) in db[h_len..].iter().enumerate() {
+            let is_one = b == 0x01;
+            // remember the position of the first 0x01
+            separator = if looking_for_one & is_one { i } else { separator };
+            // any non zero byte before it is invalid
+            valid &= !looking_for_one | is_one | (b == 0x00);
+            looking_for_one &= !is_one;
+        }
+        valid &= !looking_for_one;
+
+        if !valid {
+            return Err(Error::DecryptionFailed);
+        }
+        Ok(db[h_len + separator + 1..].to_vec())
+    }
+}
+
+fn xor_in_place(data: &mut [u8], mask: &[u8]) {
+    for (d, m) in data.iter_mut().zip(mask) {
+        *d ^= m;
+    }
+}
+
+#[test]
+// RFC 8017 RSAES-OAEP test vectors (oaep-vect.txt, example 1.1)
+fn test_oaep_known_vector() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::hash::SHA1;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_example_key();
+    let oaep = OAEP::<SHA1>::new(b"");
+
+    let message = hex_decode("
+        66 28 19 4e 12 07 3d b0 3b a9 4c da 9e f9 53 23 97 d5 0d ba 79 b9 87 00 4a fe fe 34
+    ").unwrap();
+    let seed = hex_decode("18 b7 76 ea 21 06 9d 69 77 6a 33 e9 6b ad 48 e1 dd a0 a5 ef").unwrap();
+    let expected = hex_decode("
+        35 4f e6 7b 4a 12 6d 5d 35 fe 36 c7 77 79 1a 3f 7b a1 3d ef 48 4e 2d 39 08 af f7 22 fa d4 68 fb
+        21 69 6d e9 5d 0b e9 11 c2 d3 17 4f 8a fc c2 01 03 5f 7b 6d 8e 69 40 2d e5 45 16 18 c2 1a 53 5f
+        a9 d7 bf c5 b8 dd 9f c2 43 f8 cf 92 7d b3 13 22 d6 e8 81 ea a9 1a 99 61 70 e6 57 a0 5a 26 64 26
+        d9 8c 88 00 3f 84 77 c1 22 70 94 a0 d9 fa 1e 8c 40 24 30 9c e1 ec cc b5 21 00 35 d4 7a c7 2e 8a
+    ").unwrap();
+
+    let ciphertext = oaep.encrypt_with_seed(&key, &message, &seed).unwrap();
+    assert_eq!(ciphertext, expected, "RSAES-OAEP failed encryption");
+    assert_eq!(oaep.decrypt(&key, &ciphertext).unwrap(), message, "RSAES-OAEP failed decryption");
+}
+
+#[test]
+fn test_oaep_roundtrip_and_errors() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::hash::SHA1;
+
+    let key = rfc8017_example_key();
+    let oaep = OAEP::<SHA1>::new(b"my label");
+
+    // random seeds make encryption probabilistic
+    let message = b"attack at dawn";
+    let c1 = oaep.encrypt(&key, message).unwrap();
+    let c2 = oaep.encrypt(&key, message).unwrap();
+    assert_ne!(c1, c2);
+    assert_eq!(oaep.decrypt(&key, &c1).unwrap(), message);
+    assert_eq!(oaep.decrypt(&key, &c2).unwrap(), message);
+
+    // empty and maximum length messages
+    let max = vec![0xFF; oaep.max_message_len(&key)];
+    assert_eq!(max.len(), 128 - 2 * 20 - 2);
+    for message in [&[][..], &max[..]] {
+        let ciphertext = oaep.encrypt(&key, message).unwrap();
+        assert_eq!(oaep.decrypt(&key, &ciphertext).unwrap(), message);
+    }
+    assert_eq!(oaep.encrypt(&key, &[0u8; 87]), Err(Error::MessageTooLong));
+
+    // wrong label, tampered and truncated ciphertexts all fail the same way
+    assert_eq!(OAEP::<SHA1>::new(b"other").decrypt(&key, &c1), Err(Error::DecryptionFailed));
+    let mut tampered = c1.clone();
+    tampered[64] ^= 1;
+    assert_eq!(oaep.decrypt(&key, &tampered), Err(Error::DecryptionFailed));
+    assert_eq!(oaep.decrypt(&key, &c1[1..]), Err(Error::DecryptionFailed));
+}
@@ -0,0 +1,220 @@
+/*
+* RSAES-PKCS1-v1_5 encryption scheme (RFC 8017 section 7.2)
+*
+*   EM = 0x00 || 0x02 || PS || 0x00 || M       (PS = at least 8 random non zero bytes)
+*
+* NOTE: kept for compatibility only, new applications should use OAEP.
+* If an attacker can tell whether a ciphertext is PKCS#1 v1.5 conforming
+* (different errors, timing, ...) it can decrypt any ciphertext with
+* Bleichenbacher's 1998 attack (see attacks::bleichenbacher).
+* decrypt_implicit() removes the oracle by never failing on a padding error.
+*/
+
+use std::cell::Cell;
+
+use num_bigint::BigUint;
+use rand::Rng;
+
+use crate::{Error, Result};
+use crate::ciphers::rsa::rsa::RSA;
+use crate::ciphers::rsa::rsa_utils::{i2osp, os2ip};
+use crate::hash::{Hash, SHA1, mgf1};
+
+pub struct PKCS1v15Encrypt;
+
+impl PKCS1v15Encrypt {
+    // maximum message length in bytes for the given key
+    pub fn max_message_len(&self, key: &RSA) -> usize {
+        key.modulus_len().saturating_sub(11)
+    }
+
+    // encrypts message with fresh random padding
+    pub fn encrypt(&self, key: &RSA, message: &[u8]) -> Result<Vec<u8>> {
+        let k = key.modulus_len();
+
+        // 1. length checking
+        if k < 11 || message.len() > k - 11 {
+            return Err(Error::MessageTooLong);
+        }
+
+        // 2. EM = 0x00 || 0x02 || PS || 0x00 || M
+        let mut rng = rand::rng();
+        let mut em = vec![0x00, 0x02];
+        em.extend((0..k - message.len() - 3).map(|_| rng.random_range(1..=255u8)));
+        em.push(0x00);
+        em.extend_from_slice(message);
+
+        // 3. textbook RSA on the encoded message
+        let c = key.encrypt_message(&os2ip(&em))?;
+        i2osp(&c, k)
+    }
+
+    // decrypts and decodes ciphertext
+    // every failure is reported as Error::DecryptionFailed
+    pub fn decrypt(&self, key: &RSA, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let em = Self::raw_decrypt(key, ciphertext)?;
+        Self::decode(&em).map(|m| m.to_vec()).ok_or(Error::DecryptionFailed)
+    }
+
+    // decrypts ciphertext with implicit rejection:
+    // if the padding is malformed a synthetic message derived from the private key
+    // and the ciphertext is returned, so the caller can't tell the two cases apart
+    pub fn decrypt_implicit(&self, key: &RSA, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let em = Self::raw_decrypt(key, ciphertext)?;
+        match Self::decode(&em) {
+            Some(message) => Ok(message.to_vec()),
+            None => Ok(Self::synthetic_message(key, ciphertext)),
+        }
+    }
+
+    // textbook RSA decryption of ciphertext into the k bytes encoded message
+    fn raw_decrypt(key: &RSA, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let k = key.modulus_len();
+        if ciphertext.len() != k || k < 11 {
+            return Err(Error::DecryptionFailed);
+        }
+        let m = key
+            .decrypt_message(&os2ip(ciphertext))
+            .map_err(|_| Error::DecryptionFailed)?;
+        i2osp(&m, k).map_err(|_| Error::DecryptionFailed)
+    }
+
+    // checks 0x00 || 0x02 || PS || 0x00 || M and returns M
+    // NOTE: & instead of && so that every check is always evaluated
+    pub(crate) fn decode(em: &[u8]) -> Option<&[u8]> {
+        if em.len() < 11 {
+            return None;
+        }
+
+        let mut valid = (em[0] == 0x00) & (em[1] == 0x02);
+        let mut looking_for_zero = true;
+        let mut separator = 0usize;
+        for (i, &b) in em.iter().enumerate().skip(2) {
+            let is_zero = b == 0x00;
+            separator = if looking_for_zero & is_zero { i } else { separator };
+            looking_for_zero &= !is_zero;
+        }
+        // the separator must exist and PS must be at least 8 bytes long
+        valid &= !looking_for_zero & (separator >= 10);
+
+        if !valid {
+            return None;
+        }
+        Some(&em[separator + 1..])
+    }
+
+    // deterministic random-looking message of a plausible length
+    // derived from the private key and the ciphertext
+    fn synthetic_message(key: &RSA, ciphertext: &[u8]) -> Vec<u8> {
+        // key derivation key bound to the private exponent
+        let mut hasher = SHA1::new();
+        hasher.update(&key.d().to_bytes_be());
+        hasher.update(ciphertext);
+        let kdk = hasher.finalize();
+
+        let max_len = key.modulus_len() - 11;
+        let stream = mgf1::<SHA1>(&kdk, max_len + 2);
+        let len = u16::from_be_bytes([stream[0], stream[1]]) as usize % (max_len + 1);
+        stream[2..2 + len].to_vec()
+    }
+}
+
+/*
+* Padding oracle (opt-in, for attack demonstrations only!)
+* wraps a private key and answers a single question: "does this ciphertext decrypt
+* to something starting with 0x00 || 0x02 ?", exactly what a server leaks when it
+* reports padding errors differently from other errors
+*/
+pub struct PaddingOracle<'a> {
+    key: &'a RSA,
+    queries: Cell<u64>,
+}
+
+impl<'a> PaddingOracle<'a> {
+    pub fn new(key: &'a RSA) -> Self {
+        Self { key, queries: Cell::new(0) }
+    }
+
+    // public part of the wrapped key: (n, e)
+    pub fn public_key(&self) -> (&BigUint, &BigUint) {
+        (self.key.n(), self.key.e())
+    }
+
+    // true if the ciphertext (as an integer) decrypts to a 0x00 || 0x02 prefixed block
+    pub fn is_conforming(&self, c: &BigUint) -> bool {
+        self.queries.set(self.queries.get() + 1);
+        let k = self.key.modulus_len();
+        match self.key.decrypt_message(c).and_then(|m| i2osp(&m, k)) {
+            Ok(em) => em[0] == 0x00 && em[1] == 0x02,
+            Err(_) => false,
+        }
+    }
+
+    // number of questions asked so far
+    pub fn queries(&self) -> u64 {
+        self.queries.get()
+    }
+}
+
+#[test]
+// ciphertext produced by pyca/cryptography (OpenSSL backend) with the RFC 8017 example key
+fn test_pkcs1v15_known_ciphertext() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_example_key();
+    let ciphertext = hex_decode("
+        3532183e3b34974d588f53e806423fbb0eec1277e22af1d6533624371f0c57c3
+        6b4e939bdd4f36a4724f8fce7397c58df58d1bac96fa5e79210c0ceef056c319
+        42ec34978e17656de53bed8c0c0d219cd56950d1fe28b7ea36b5024301a1d6c0
+        ac42769dffb4ade3fa1ce22d0ec46b5dc513d61622ead3eaa5c0541b1da713c1
+    ").unwrap();
+
+    assert_eq!(PKCS1v15Encrypt.decrypt(&key, &ciphertext).unwrap(), b"attack at dawn");
+    assert_eq!(PKCS1v15Encrypt.decrypt_implicit(&key, &ciphertext).unwrap(), b"attack at dawn");
+}
+
+#[test]
+fn test_pkcs1v15_roundtrip_and_errors() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+
+    let key = rfc8017_example_key();
+    let scheme = PKCS1v15Encrypt;
+
+    let max = vec![0xAB; scheme.max_message_len(&key)];
+    for message in [&b""[..], b"attack at dawn", &max] {
+        let ciphertext = scheme.encrypt(&key, message).unwrap();
+        assert_eq!(scheme.decrypt(&key, &ciphertext).unwrap(), message);
+    }
+    assert_eq!(scheme.encrypt(&key, &[0u8; 118]), Err(Error::MessageTooLong));
+
+    // malformed padding is a single error
+    let ciphertext = scheme.encrypt(&key, b"attack at dawn").unwrap();
+    let mut tampered = ciphertext.clone();
+    tampered[0] ^= 0x01;
+    assert_eq!(scheme.decrypt(&key, &tampered), Err(Error::DecryptionFailed));
+    assert_eq!(scheme.decrypt(&key, &ciphertext[1..]), Err(Error::DecryptionFailed));
+
+    // with implicit rejection it isn't an error at all, and the result is stable
+    let synthetic = scheme.decrypt_implicit(&key, &tampered).unwrap();
+    assert_eq!(scheme.decrypt_implicit(&key, &tampered).unwrap(), synthetic);
+    assert!(synthetic.len() <= scheme.max_message_len(&key));
+}
+
+#[test]
+fn test_pkcs1v15_decode() {
+    let mut em = vec![0x00, 0x02];
+    em.extend([0xFF; 8]);
+    em.push(0x00);
+    em.extend(b"hi");
+    assert_eq!(PKCS1v15Encrypt::decode(&em), Some(&b"hi"[..]));
+
+    // PS shorter than 8 bytes
+    let short = [&[0x00, 0x02], &[0xFF; 7][..], &[0x00], b"hi"].concat();
+    assert_eq!(PKCS1v15Encrypt::decode(&short), None);
+    // no separator
+    assert_eq!(PKCS1v15Encrypt::decode(&[0x00, 0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), None);
+    // wrong block type
+    em[1] = 0x01;
+    assert_eq!(PKCS1v15Encrypt::decode(&em), None);
+}
@@ -1,131 +1,290 @@
 /*
-*   Simplified (and unsecure) RSA implementation in pure Rust
+*   RSA implementation in pure Rust over arbitrary-precision integers
 *
-*   NOTE: for simplcity we work with u64 which isn't enough to provide
-*   actual security, but this is just a toy implementation so it's fine
+*   NOTE: this is still textbook RSA (deterministic and malleable),
+*   real applications must use a padding scheme on top of it
 */
 
 use num_bigint::BigUint;
-use num_integer::{gcd, ExtendedGcd, Integer};
-use rand::Rng;
-use primes::is_prime;
+
+use crate::{Error, Result};
+use crate::ciphers::rsa::rsa_utils::*;
+
+// commonly used public exponent (2^16 + 1)
+pub const DEFAULT_PUBLIC_EXPONENT: u32 = 65537;
+
+// private key parameters used by the Chinese Remainder Theorem
+pub struct CrtParams {
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,    // d mod (p - 1)
+    dq: BigUint,    // d mod (q - 1)
+    qinv: BigUint,  // q^-1 mod p
+}
 
 pub struct RSA {
-    n: u64,
-    e: u64,     // public exponent
-    d: u64      // private exponent
+    n: BigUint,
+    e: BigUint,     // public exponent
+    d: BigUint,     // private exponent
+    // only known if the keypair was built from its primes
+    crt: Option<CrtParams>,
+    // verify every private key operation with the public exponent
+    fault_check: bool,
 }
 
 impl RSA {
     // initialize the cypher with the provided keypair
-    pub fn new(n: u64, e: u64, d: u64) -> Self {
-        Self { n, e, d }
+    pub fn new(n: BigUint, e: BigUint, d: BigUint) -> Self {
+        Self { n, e, d, crt: None, fault_check: true }
     }
 
     // initialize the cypher by generating a new keypair
-    pub fn gen_keypair() -> Self {
-        // 1. generate a p in [2^16, 2^20]
-        let p = gen_prime_number_range(1u64 << 16, 1u64 << 20);
-        // 2. generate a q in [2^16, 2^20]
-        let q = gen_prime_number_range(1u64 << 16, 1u64 << 20);
-        
-        // 3. compute n = p x q
-        let n = p * q;
-
-        // 4. compute phi(n)
-        let phi_n = (p-1) * (q-1);
-
-        // 5. chose e
-        // commonly used value of 65537
-        let e = 65537;
-        // make sure it is coprime with phi
-        assert_eq!(gcd(e, phi_n), 1, "e is not coprime with phi_n");
-
-        // 6. chose d as e^-1 mod phi
-        // panics if e has no inverse mod phi
-        let d = mod_inverse(e, phi_n).unwrap();
-
-        Self { n, e, d }
+    // with a modulus of the given size (es: 2048, 3072, 4096)
+    pub fn gen_keypair(bits: u64) -> Result<Self> {
+        if bits < 32 || !bits.is_multiple_of(2) {
+            return Err(Error::InvalidParameter("RSA modulus size must be an even number of bits >= 32"));
+        }
+
+        loop {
+            // 1. generate two distinct primes of bits / 2 bits
+            // using Miller-Rabin to determine their primality
+            let p = gen_prime(bits / 2);
+            let q = gen_prime(bits / 2);
+            if p == q {
+                continue;
+            }
+
+            // 2. chose e
+            // commonly used value of 65537
+            // if it isn't coprime with phi(n) just pick new primes
+            if let Ok(cipher) = Self::from_primes(p, q, BigUint::from(DEFAULT_PUBLIC_EXPONENT)) {
+                return Ok(cipher);
+            }
+        }
     }
-    
+
+    // initialize the cypher from the two primes and the public exponent
+    // fails if e has no inverse mod phi(n)
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Result<Self> {
+        let one = BigUint::from(1u32);
+        if p <= one || q <= one || p == q {
+            return Err(Error::InvalidParameter("p and q must be distinct primes"));
+        }
+
+        // 1. compute n = p x q
+        let n = &p * &q;
+
+        // 2. compute phi(n)
+        let phi_n = (&p - &one) * (&q - &one);
+
+        // 3. chose d as e^-1 mod phi
+        // e must be coprime with phi otherwise it has no inverse
+        let d = e.modinv(&phi_n).ok_or(Error::NoModularInverse)?;
+
+        // 4. precompute the CRT parameters
+        let dp = &d % (&p - &one);
+        let dq = &d % (&q - &one);
+        let qinv = q.modinv(&p).ok_or(Error::NoModularInverse)?;
+
+        Ok(Self { n, e, d, crt: Some(CrtParams { p, q, dp, dq, qinv }), fault_check: true })
+    }
+
+    // public modulus
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    // public exponent
+    pub fn e(&self) -> &BigUint {
+        &self.e
+    }
+
+    // private exponent, only visible to the schemes built on top of RSA
+    pub(crate) fn d(&self) -> &BigUint {
+        &self.d
+    }
+
+    // size of the modulus in bits
+    pub fn modulus_bits(&self) -> u64 {
+        self.n.bits()
+    }
+
+    // size of the modulus in bytes
+    pub fn modulus_len(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    // CRT parameters (p, q, dP, dQ, qInv) if known
+    pub fn crt_params(&self) -> Option<&CrtParams> {
+        self.crt.as_ref()
+    }
+
+    // enables or disables the fault check of the private key operations (enabled by default)
+    // NOTE: without it a single faulty CRT half leaks the factorization of n (Bellcore attack)
+    pub fn set_fault_check(&mut self, enabled: bool) {
+        self.fault_check = enabled;
+    }
+
     // outputs the cyphertext for the provided message
-    pub fn encrypt_message(&self, m: u64) -> u64 {
-        mod_pow_bigint(m, self.e, self.n)
+    // fails if m isn't smaller than n
+    pub fn encrypt_message(&self, m: &BigUint) -> Result<BigUint> {
+        if *m >= self.n {
+            return Err(Error::MessageOutOfRange);
+        }
+        Ok(m.modpow(&self.e, &self.n))
     }
 
-    pub fn decrypt_message(&self, c: u64) -> u64 {
-        mod_pow_bigint(c, self.d, self.n)
+    // fails if c isn't smaller than n
+    pub fn decrypt_message(&self, c: &BigUint) -> Result<BigUint> {
+        self.private_op(c)
     }
-}
 
-// utility function to generate a random prime number
-// in a given range
-fn gen_prime_number_range(low: u64, high: u64) -> u64 {
-    // reference to a secure randomness generator
-    let mut rng = rand::rng();
-    
-    loop {
-        // generate an odd number within the range [low, high]
-        // we can skip even numbers because they all have 2 as a factor
-        let c = rng.random_range(low..=high) | 1;
-        
-        // make sure such number is prime using Sieve method
-        // that enumerates every prime number in [2, sqrt(c)]
-        // NOTE: simpler because I'm working with u64
-        // but real 2048 bit RSA implementations use Miller-Rabin
-        if is_prime(c) {
-            return c;
+    // outputs the signature of the provided message representative
+    // fails if m isn't smaller than n
+    pub fn sign_message(&self, m: &BigUint) -> Result<BigUint> {
+        self.private_op(m)
+    }
+
+    // checks that s is a valid signature of m
+    pub fn verify_signature(&self, m: &BigUint, s: &BigUint) -> bool {
+        *s < self.n && s.modpow(&self.e, &self.n) == *m
+    }
+
+    // computes x^d mod n, using the CRT if the primes are known
+    fn private_op(&self, x: &BigUint) -> Result<BigUint> {
+        if *x >= self.n {
+            return Err(Error::MessageOutOfRange);
+        }
+
+        let out = match &self.crt {
+            Some(crt) => crt.private_op(x),
+            None => x.modpow(&self.d, &self.n),
+        };
+
+        // make sure that out^e = x mod n before releasing the result
+        if self.fault_check && out.modpow(&self.e, &self.n) != *x {
+            return Err(Error::FaultDetected);
         }
+
+        Ok(out)
     }
 }
 
-// tryes to finds the inverse of a mod n 
-fn mod_inverse(a: u64, n: u64) -> Option<u64> {
-    // we use the extended_gcd() from the num_integer crate to find
-    // the gcd and the Bézeut coefficients between a and n
-    // Bézeut coefficients => a·x + n·y = gcd(a, n)
-    let ExtendedGcd { gcd, x, y: _ } = (a as i64).extended_gcd(&(n as i64));
-    
-    // if the GCD(a,n) != than a has no inverse mod n
-    if gcd != 1 {
-        None
-    } else {
-        // Bézeut coefficients => a·x + n·y = gcd(a, n)
-        // such that x is the inverse of a mod n
-        let n = n as i64;
-        Some(((x % n + n) % n) as u64)
+impl CrtParams {
+    // first prime factor of n
+    pub fn p(&self) -> &BigUint {
+        &self.p
     }
-}
 
-// performs base^exp mod n using a bigint crate,
-// conventient because I don't need to worry about overflows
-fn mod_pow_bigint(base: u64, exp: u64, n: u64) -> u64 {
-    // convert the parameters into BigUint types
-    let base = BigUint::from(base);
-    let exp = BigUint::from(exp);
-    let n = BigUint::from(n);
-    
-    // perform base^exp mod n using the BigUints
-    let res = base.modpow(&exp, &n);
-    
-    // convert the result back to u64
-    res.try_into().unwrap()
+    // second prime factor of n
+    pub fn q(&self) -> &BigUint {
+        &self.q
+    }
+
+    // private exponent mod (p - 1)
+    pub fn dp(&self) -> &BigUint {
+        &self.dp
+    }
+
+    // private exponent mod (q - 1)
+    pub fn dq(&self) -> &BigUint {
+        &self.dq
+    }
+
+    // inverse of q mod p
+    pub fn qinv(&self) -> &BigUint {
+        &self.qinv
+    }
+
+    // computes x^d mod n using the Chinese Remainder Theorem (Garner's formula)
+    // two exponentiations with half-size modulus and exponent are ~4x faster than one full-size
+    fn private_op(&self, x: &BigUint) -> BigUint {
+        // 1. m1 = x^dP mod p, m2 = x^dQ mod q
+        let m1 = (x % &self.p).modpow(&self.dp, &self.p);
+        let m2 = (x % &self.q).modpow(&self.dq, &self.q);
+
+        // 2. h = qInv * (m1 - m2) mod p
+        // NOTE: + p keeps the difference positive
+        let h = (&self.qinv * ((&m1 + &self.p - (&m2 % &self.p)) % &self.p)) % &self.p;
+
+        // 3. m = m2 + h * q
+        m2 + h * &self.q
+    }
 }
 
 #[test]
 fn test_keypair_gen() {
-    let cipher = RSA::gen_keypair();
-    println!("PUB: (e: {}, n: {})", cipher.e, cipher.n);
-    println!("PRIV: (d: {}, n: {})", cipher.d, cipher.n);
-
-    let message = 2;
-    let ciphertext = cipher.encrypt_message(message);
-    println!("Ciphertext: {}", ciphertext);
-    let decryped = cipher.decrypt_message(ciphertext);
-    println!("Decrypted: {}", decryped);
+    let cipher = RSA::gen_keypair(2048).unwrap();
+    assert_eq!(cipher.modulus_bits(), 2048);
+
+    // the CRT parameters are consistent with the keypair
+    let crt = cipher.crt_params().unwrap();
+    let one = BigUint::from(1u32);
+    assert_eq!(&crt.p * &crt.q, cipher.n);
+    assert_eq!(crt.dp, &cipher.d % (&crt.p - &one));
+    assert_eq!(crt.dq, &cipher.d % (&crt.q - &one));
+    assert_eq!((&crt.q * &crt.qinv) % &crt.p, one);
+
+    // a message as large as the modulus allows
+    let message = &cipher.n - 2u32;
+    let ciphertext = cipher.encrypt_message(&message).unwrap();
+    let decryped = cipher.decrypt_message(&ciphertext).unwrap();
     assert_eq!(message, decryped, "decryption failed");
 }
 
+#[test]
+fn test_crt_matches_plain_exponentiation() {
+    let cipher = RSA::gen_keypair(1024).unwrap();
+    let c = &cipher.n - 12345u32;
+
+    let plain = c.modpow(&cipher.d, &cipher.n);
+    assert_eq!(cipher.crt_params().unwrap().private_op(&c), plain);
+    assert_eq!(cipher.decrypt_message(&c).unwrap(), plain);
+
+    // signatures
+    let m = BigUint::from(0xC0FFEEu32);
+    let s = cipher.sign_message(&m).unwrap();
+    assert!(cipher.verify_signature(&m, &s));
+    assert!(!cipher.verify_signature(&(m + 1u32), &s));
+}
+
+#[test]
+// Boneh, DeMillo, Lipton - "On the Importance of Checking Cryptographic Protocols for Faults"
+fn test_crt_fault_attack() {
+    let mut cipher = RSA::gen_keypair(1024).unwrap();
+    let m = BigUint::from(42u32);
+
+    // inject a fault in the p half of the CRT
+    if let Some(crt) = cipher.crt.as_mut() {
+        crt.dp += 1u32;
+    }
+
+    // the fault check refuses to release the faulty signature
+    assert_eq!(cipher.sign_message(&m), Err(Error::FaultDetected));
+
+    // without it, gcd(s^e - m, n) reveals q
+    cipher.set_fault_check(false);
+    let s = cipher.sign_message(&m).unwrap();
+    let se = s.modpow(&cipher.e, &cipher.n);
+    let diff = if se > m { se - &m } else { &m - se };
+    let factor = num_integer::Integer::gcd(&diff, &cipher.n);
+    assert_eq!(&factor, cipher.crt_params().unwrap().q());
+}
+
+#[test]
+#[ignore = "generating 3072 and 4096 bit keys is slow, run with cargo test -- --ignored"]
+fn test_keypair_gen_sizes() {
+    for bits in [3072, 4096] {
+        let cipher = RSA::gen_keypair(bits).unwrap();
+        assert_eq!(cipher.modulus_bits(), bits);
+        assert_eq!(cipher.modulus_len() as u64, bits / 8);
+
+        let message = BigUint::from_bytes_be(&vec![0xAB; cipher.modulus_len() - 1]);
+        let ciphertext = cipher.encrypt_message(&message).unwrap();
+        assert_eq!(cipher.decrypt_message(&ciphertext).unwrap(), message);
+    }
+}
+
 #[test]
 fn test_rsa_known_params() {
     const RSA_TEST_PAIRS: [(u64, u64, u64, u64, u64, u64); 9] = [
@@ -145,12 +304,28 @@ fn test_rsa_known_params() {
         (11, 13, 143, 120, 17, 113), // 17×113 = 1921 ≡ 1 mod 120
     ];
     
-    let message = 2;
-    for (_, _, n, _, e, d) in RSA_TEST_PAIRS {
-        let cipher = RSA::new(n, e, d);
-        let ciphertext = cipher.encrypt_message(message);
+    let message = BigUint::from(2u32);
+    for (p, q, n, _, e, d) in RSA_TEST_PAIRS {
+        let cipher = RSA::new(n.into(), e.into(), d.into());
+        let ciphertext = cipher.encrypt_message(&message).unwrap();
         println!("M={} with e={} n={} => {}", message, e, n, ciphertext);
-        let decrypted = cipher.decrypt_message(ciphertext);
+        let decrypted = cipher.decrypt_message(&ciphertext).unwrap();
         assert_eq!(decrypted, message);
+
+        // from_primes derives the same private exponent
+        let cipher = RSA::from_primes(p.into(), q.into(), e.into()).unwrap();
+        assert_eq!(cipher.d, BigUint::from(d));
     }
 }
+
+#[test]
+fn test_rsa_errors() {
+    // e = 5 isn't coprime with phi(n) = 40
+    let e = BigUint::from(5u32);
+    assert_eq!(RSA::from_primes(5u32.into(), 11u32.into(), e).err(), Some(Error::NoModularInverse));
+
+    let cipher = RSA::from_primes(5u32.into(), 11u32.into(), 3u32.into()).unwrap();
+    assert_eq!(cipher.encrypt_message(&55u32.into()), Err(Error::MessageOutOfRange));
+    assert_eq!(cipher.decrypt_message(&u64::MAX.into()), Err(Error::MessageOutOfRange));
+    assert!(RSA::gen_keypair(31).is_err());
+}
@@ -0,0 +1,209 @@
+use num_bigint::BigUint;
+use rand::RngCore;
+
+use crate::{Error, Result};
+
+// number of Miller-Rabin rounds, each one has at most 1/4 probability
+// of declaring a composite number prime (so the error is at most 2^-80)
+pub const MILLER_RABIN_ROUNDS: usize = 40;
+
+// primes below this bound are used to quickly discard candidates by trial division
+const SMALL_PRIMES_BOUND: usize = 2000;
+
+// generates a random prime number of exactly the given number of bits
+pub fn gen_prime(bits: u64) -> BigUint {
+    let small_primes = small_primes();
+
+    loop {
+        // generate a random odd number with the two most significant bits set
+        // so that the product of two such primes has exactly 2 * bits bits
+        let mut candidate = random_bits(bits);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(bits - 2, true);
+        candidate.set_bit(0, true);
+
+        // cheap trial division first, Miller-Rabin only on the survivors
+        if small_primes.iter().any(|&p| &candidate % p == BigUint::ZERO) {
+            continue;
+        }
+        if is_probable_prime(&candidate, MILLER_RABIN_ROUNDS) {
+            return candidate;
+        }
+    }
+}
+
+// Miller-Rabin probabilistic primality test
+// writes n - 1 = 2^s * d with d odd and, for random witnesses a, checks that either
+// a^d = 1 mod n or a^(2^r * d) = -1 mod n for some r in [0, s)
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    let three = BigUint::from(3u32);
+
+    // handle the small cases
+    if *n < two {
+        return false;
+    }
+    if *n <= three {
+        return true;
+    }
+    if !n.bit(0) {
+        return false;
+    }
+
+    // 1. n - 1 = 2^s * d
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    'witness: for _ in 0..rounds {
+        // 2. pick a random witness a in [2, n - 2]
+        let a = random_below(&(n - &three)) + &two;
+
+        // 3. check the sequence a^d, a^2d, ..., a^(2^(s-1) d)
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+
+        // a is a witness of the compositeness of n
+        return false;
+    }
+
+    true
+}
+
+// generates a random number of at most the given number of bits
+pub fn random_bits(bits: u64) -> BigUint {
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+    rand::rng().fill_bytes(&mut bytes);
+
+    // clear the extra bits of the most significant byte
+    let extra = bytes.len() as u64 * 8 - bits;
+    if let Some(msb) = bytes.first_mut() {
+        *msb &= 0xFF >> extra;
+    }
+    BigUint::from_bytes_be(&bytes)
+}
+
+// generates a random number in [0, bound)
+pub fn random_below(bound: &BigUint) -> BigUint {
+    // rejection sampling on the bit length of bound
+    loop {
+        let candidate = random_bits(bound.bits());
+        if candidate < *bound {
+            return candidate;
+        }
+    }
+}
+
+// Sieve of Eratosthenes for the primes below SMALL_PRIMES_BOUND
+fn small_primes() -> Vec<u32> {
+    let mut is_prime = vec![true; SMALL_PRIMES_BOUND];
+    let mut primes = Vec::new();
+    for i in 2..SMALL_PRIMES_BOUND {
+        if is_prime[i] {
+            primes.push(i as u32);
+            for j in (i * i..SMALL_PRIMES_BOUND).step_by(i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+// Integer-to-Octet-String primitive (RFC 8017 section 4.1)
+// converts x into a big-endian byte string of exactly len bytes
+pub fn i2osp(x: &BigUint, len: usize) -> Result<Vec<u8>> {
+    let bytes = x.to_bytes_be();
+    if *x == BigUint::ZERO {
+        return Ok(vec![0u8; len]);
+    }
+    if bytes.len() > len {
+        return Err(Error::MessageOutOfRange);
+    }
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend(bytes);
+    Ok(out)
+}
+
+// Octet-String-to-Integer primitive (RFC 8017 section 4.2)
+pub fn os2ip(bytes: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(bytes)
+}
+
+// RFC 8017 example key (oaep-vect.txt, example 1: 1024 bit key)
+// shared by the tests of the RSA schemes
+#[cfg(test)]
+pub fn rfc8017_example_key() -> crate::RSA {
+    use crate::utils::hex_decode;
+
+    let p = hex_decode("
+        d3 27 37 e7 26 7f fe 13 41 b2 d5 c0 d1 50 a8 1b 58 6f b3 13 2b ed 2f 8d 52 62 86 4a 9c b9 f3 0a
+        f3 8b e4 48 59 8d 41 3a 17 2e fb 80 2c 21 ac f1 c1 1c 52 0c 2f 26 a4 71 dc ad 21 2e ac 7c a3 9d
+    ").unwrap();
+    let q = hex_decode("
+        cc 88 53 d1 d5 4d a6 30 fa c0 04 f4 71 f2 81 c7 b8 98 2d 82 24 a4 90 ed be b3 3d 3e 3d 5c c9 3c
+        47 65 70 3d 1d d7 91 64 2f 1f 11 6a 0d d8 52 be 24 19 b2 af 72 bf e9 a0 30 e8 60 b0 28 8b 5d 77
+    ").unwrap();
+    crate::RSA::from_primes(os2ip(&p), os2ip(&q), BigUint::from(65537u32)).unwrap()
+}
+
+// RFC 8017 example key (pss-vect.txt and pkcs1v15sign-vect.txt, example 1: 1024 bit key)
+#[cfg(test)]
+pub fn rfc8017_signature_example_key() -> crate::RSA {
+    use crate::utils::hex_decode;
+
+    let p = hex_decode("
+        e7 e8 94 27 20 a8 77 51 72 73 a3 56 05 3e a2 a1 bc 0c 94 aa 72 d5 5c 6e 86 29 6b 2d fc 96 79 48
+        c0 a7 2c bc cc a7 ea cb 35 70 6e 09 a1 df 55 a1 53 5b d9 b3 cc 34 16 0b 3b 6d cd 3e da 8e 64 43
+    ").unwrap();
+    let q = hex_decode("
+        b6 9d ca 1c f7 d4 d7 ec 81 e7 5b 90 fc ca 87 4a bc de 12 3f d2 70 01 80 aa 90 47 9b 6e 48 de 8d
+        67 ed 24 f9 f1 9d 85 ba 27 58 74 f5 42 cd 20 dc 72 3e 69 63 36 4a 1f 94 25 45 2b 26 9a 67 99 fd
+    ").unwrap();
+    crate::RSA::from_primes(os2ip(&p), os2ip(&q), BigUint::from(65537u32)).unwrap()
+}
+
+// Unit Tests
+#[test]
+fn test_miller_rabin_known_numbers() {
+    let primes: [u64; 6] = [2, 3, 65537, 2147483647, 1000000007, 18446744073709551557];
+    for p in primes {
+        assert!(is_probable_prime(&BigUint::from(p), MILLER_RABIN_ROUNDS), "{} is prime", p);
+    }
+
+    // Carmichael numbers fool the Fermat test but not Miller-Rabin
+    let composites: [u64; 6] = [1, 4, 561, 41041, 3215031751, 18446744073709551555];
+    for c in composites {
+        assert!(!is_probable_prime(&BigUint::from(c), MILLER_RABIN_ROUNDS), "{} is composite", c);
+    }
+
+    // Mersenne prime 2^521 - 1 and its composite neighbour 2^523 - 1
+    let m521 = (BigUint::from(1u32) << 521u32) - 1u32;
+    assert!(is_probable_prime(&m521, MILLER_RABIN_ROUNDS));
+    let m523 = (BigUint::from(1u32) << 523u32) - 1u32;
+    assert!(!is_probable_prime(&m523, MILLER_RABIN_ROUNDS));
+}
+
+#[test]
+fn test_i2osp_os2ip() {
+    let x = BigUint::from(0x0102u32);
+    assert_eq!(i2osp(&x, 4).unwrap(), [0, 0, 1, 2]);
+    assert_eq!(os2ip(&[0, 0, 1, 2]), x);
+    assert_eq!(i2osp(&BigUint::ZERO, 2).unwrap(), [0, 0]);
+    assert_eq!(i2osp(&x, 1), Err(Error::MessageOutOfRange));
+}
+
+#[test]
+fn test_gen_prime_bit_length() {
+    let p = gen_prime(256);
+    assert_eq!(p.bits(), 256);
+    assert!(p.bit(254) && p.bit(0));
+}
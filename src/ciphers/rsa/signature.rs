@@ -0,0 +1,514 @@
+/*
+* RSA signature schemes (RFC 8017 section 8, PKCS#1 v2.2)
+*
+* RSASSA-PKCS1-v1_5 (deterministic):
+*   EM = 0x00 || 0x01 || 0xFF...0xFF || 0x00 || DigestInfo(H(M))
+*
+* RSASSA-PSS (probabilistic, provably secure):
+*   M' = 0x00 * 8 || H(M) || salt
+*   DB = PS || 0x01 || salt                    (PS = zeros)
+*   EM = (DB XOR MGF(H(M'))) || H(M') || 0xbc
+*
+* verification of PKCS#1 v1.5 re-encodes the message and compares the whole
+* encoded message instead of parsing it, so that sloppy parsers forgery tricks
+* (es: Bleichenbacher 2006 on e = 3) are not possible
+*/
+
+use std::marker::PhantomData;
+
+use rand::RngCore;
+
+use crate::{Error, Result};
+use crate::ciphers::rsa::rsa::RSA;
+use crate::ciphers::rsa::rsa_utils::{i2osp, os2ip};
+use crate::hash::{Hash, SHA1, SHA224, SHA256, SHA384, SHA512, SHA512_256, SHA3_224, SHA3_256, SHA3_384, SHA3_512, mgf1};
+use crate::utils::ct_eq;
+
+// hash functions that can be used by RSASSA-PKCS1-v1_5
+pub trait DigestInfo: Hash {
+    // DER encoding of the DigestInfo structure up to the digest itself
+    // (AlgorithmIdentifier of the hash and OCTET STRING header)
+    const DIGEST_INFO_PREFIX: &'static [u8];
+}
+
+impl DigestInfo for SHA1 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+    ];
+}
+
+// SHA-2 and SHA-3 share the NIST hashAlgs arc 2.16.840.1.101.3.4.2.x,
+// so their prefixes only differ in the last OID byte and the digest length
+macro_rules! nist_digest_info {
+    ($hash:ident, $oid:expr, $len:expr) => {
+        impl DigestInfo for $hash {
+            const DIGEST_INFO_PREFIX: &'static [u8] = &[
+                0x30, 0x11 + $len, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, $oid,
+                0x05, 0x00, 0x04, $len,
+            ];
+        }
+    };
+}
+
+nist_digest_info!(SHA256, 0x01, 0x20);
+nist_digest_info!(SHA384, 0x02, 0x30);
+nist_digest_info!(SHA512, 0x03, 0x40);
+nist_digest_info!(SHA224, 0x04, 0x1c);
+nist_digest_info!(SHA512_256, 0x06, 0x20);
+nist_digest_info!(SHA3_224, 0x07, 0x1c);
+nist_digest_info!(SHA3_256, 0x08, 0x20);
+nist_digest_info!(SHA3_384, 0x09, 0x30);
+nist_digest_info!(SHA3_512, 0x0a, 0x40);
+
+pub struct PKCS1v15Sign<H: DigestInfo> {
+    hash: PhantomData<H>,
+}
+
+impl<H: DigestInfo> Default for PKCS1v15Sign<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: DigestInfo> PKCS1v15Sign<H> {
+    pub fn new() -> Self {
+        Self { hash: PhantomData }
+    }
+
+    // signs message with the private key
+    pub fn sign(&self, key: &RSA, message: &[u8]) -> Result<Vec<u8>> {
+        let k = key.modulus_len();
+        let em = Self::encode(message, k)?;
+        let s = key.sign_message(&os2ip(&em))?;
+        i2osp(&s, k)
+    }
+
+    // verifies signature of message with the public key
+    pub fn verify(&self, key: &RSA, message: &[u8], signature: &[u8]) -> Result<()> {
+        let k = key.modulus_len();
+
+        // 1. length checking
+        if signature.len() != k {
+            return Err(Error::InvalidSignature);
+        }
+
+        // 2. s^e mod n
+        let m = key
+            .encrypt_message(&os2ip(signature))
+            .map_err(|_| Error::InvalidSignature)?;
+        let em = i2osp(&m, k).map_err(|_| Error::InvalidSignature)?;
+
+        // 3. compare with the expected encoding
+        let expected = Self::encode(message, k).map_err(|_| Error::InvalidSignature)?;
+        if !ct_eq(&em, &expected) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    // EMSA-PKCS1-v1_5 encoding of message into em_len bytes
+    fn encode(message: &[u8], em_len: usize) -> Result<Vec<u8>> {
+        // T = DigestInfo(H(M))
+        let mut t = H::DIGEST_INFO_PREFIX.to_vec();
+        t.extend(H::digest(message));
+
+        // at least 8 bytes of 0xFF padding
+        if em_len < t.len() + 11 {
+            return Err(Error::MessageTooLong);
+        }
+
+        let mut em = vec![0x00, 0x01];
+        em.resize(em_len - t.len() - 1, 0xFF);
+        em.push(0x00);
+        em.extend(t);
+        Ok(em)
+    }
+}
+
+// H: hash used for the message, G: hash used by MGF1 (usually the same)
+pub struct PSS<H: Hash, G: Hash = H> {
+    // length of the random salt in bytes (usually H::OUTPUT_SIZE)
+    pub salt_len: usize,
+    hash: PhantomData<(H, G)>,
+}
+
+impl<H: Hash, G: Hash> PSS<H, G> {
+    pub fn new(salt_len: usize) -> Self {
+        Self { salt_len, hash: PhantomData }
+    }
+
+    // signs message with the private key and a fresh random salt
+    pub fn sign(&self, key: &RSA, message: &[u8]) -> Result<Vec<u8>> {
+        let mut salt = vec![0u8; self.salt_len];
+        rand::rng().fill_bytes(&mut salt);
+        self.sign_with_salt(key, message, &salt)
+    }
+
+    // signs message using the provided salt (deterministic, for test vectors)
+    fn sign_with_salt(&self, key: &RSA, message: &[u8], salt: &[u8]) -> Result<Vec<u8>> {
+        let em_bits = key
+            .modulus_bits()
+            .checked_sub(1)
+            .ok_or(Error::InvalidParameter("RSA modulus can't be zero"))?;
+        let em_len = em_bits.div_ceil(8) as usize;
+        let h_len = H::OUTPUT_SIZE;
+
+        // 1. length checking
+        if em_len < h_len + salt.len() + 2 {
+            return Err(Error::MessageTooLong);
+        }
+
+        // 2. H' = Hash(0x00 * 8 || mHash || salt)
+        let h = Self::hash_with_salt(&H::digest(message), salt);
+
+        // 3. DB = PS || 0x01 || salt, masked with MGF(H')
+        let mut db = vec![0u8; em_len - salt.len() - h_len - 2];
+        db.push(0x01);
+        db.extend_from_slice(salt);
+        for (d, m) in db.iter_mut().zip(mgf1::<G>(&h, em_len - h_len - 1)) {
+            *d ^= m;
+        }
+        // clear the leftmost bits so that EM < n
+        db[0] &= 0xFF >> (8 * em_len as u64 - em_bits);
+
+        // 4. EM = maskedDB || H' || 0xbc
+        let mut em = db;
+        em.extend(h);
+        em.push(0xbc);
+
+        let s = key.sign_message(&os2ip(&em))?;
+        i2osp(&s, key.modulus_len())
+    }
+
+    // verifies signature of message with the public key
+    pub fn verify(&self, key: &RSA, message: &[u8], signature: &[u8]) -> Result<()> {
+        let em_bits = key.modulus_bits().checked_sub(1).ok_or(Error::InvalidSignature)?;
+        let em_len = em_bits.div_ceil(8) as usize;
+        let h_len = H::OUTPUT_SIZE;
+
+        // 1. length checking
+        if signature.len() != key.modulus_len() || em_len < h_len + self.salt_len + 2 {
+            return Err(Error::InvalidSignature);
+        }
+
+        // 2. s^e mod n
+        let m = key
+            .encrypt_message(&os2ip(signature))
+            .map_err(|_| Error::InvalidSignature)?;
+        let em = i2osp(&m, em_len).map_err(|_| Error::InvalidSignature)?;
+
+        // 3. EM = maskedDB || H' || 0xbc, with the leftmost bits set to zero
+        let (masked_db, rest) = em.split_at(em_len - h_len - 1);
+        let (h, trailer) = rest.split_at(h_len);
+        let unused_bits = 8 * em_len as u64 - em_bits;
+        if trailer[0] != 0xbc || masked_db[0] & !(0xFF >> unused_bits) != 0 {
+            return Err(Error::InvalidSignature);
+        }
+
+        // 4. unmask DB and check PS || 0x01 || salt
+        let mut db: Vec<u8> = masked_db
+            .iter()
+            .zip(mgf1::<G>(h, em_len - h_len - 1))
+            .map(|(d, m)| d ^ m)
+            .collect();
+        db[0] &= 0xFF >> unused_bits;
+        let ps_len = em_len - h_len - self.salt_len - 2;
+        if db[..ps_len].iter().any(|&b| b != 0) || db[ps_len] != 0x01 {
+            return Err(Error::InvalidSignature);
+        }
+
+        // 5. recompute H' from the salt and compare
+        let salt = &db[ps_len + 1..];
+        if !ct_eq(h, &Self::hash_with_salt(&H::digest(message), salt)) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    // H(0x00 * 8 || m_hash || salt)
+    fn hash_with_salt(m_hash: &[u8], salt: &[u8]) -> Vec<u8> {
+        let mut hasher = H::new();
+        hasher.update(&[0u8; 8]);
+        hasher.update(m_hash);
+        hasher.update(salt);
+        hasher.finalize()
+    }
+}
+
+#[test]
+// RFC 8017 RSASSA-PKCS1-v1_5 test vectors (pkcs1v15sign-vect.txt, example 1.1)
+fn test_pkcs1v15_rfc8017_vector() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_signature_example_key;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_signature_example_key();
+    let scheme = PKCS1v15Sign::<SHA1>::new();
+    let message = hex_decode("
+        cd c8 7d a2 23 d7 86 df 3b 45 e0 bb bc 72 13 26 d1 ee 2a f8 06 cc 31 54 75 cc 6f 0d 9c 66 e1 b6
+        23 71 d4 5c e2 39 2e 1a c9 28 44 c3 10 10 2f 15 6a 0d 8d 52 c1 f4 c4 0b a3 aa 65 09 57 86 cb 76
+        97 57 a6 56 3b a9 58 fe d0 bc c9 84 e8 b5 17 a3 d5 f5 15 b2 3b 8a 41 e7 4a a8 67 69 3f 90 df b0
+        61 a6 e8 6d fa ae e6 44 72 c0 0e 5f 20 94 57 29 cb eb e7 7f 06 ce 78 e0 8f 40 98 fb a4 1f 9d 61
+        93 c0 31 7e 8b 60 d4 b6 08 4a cb 42 d2 9e 38 08 a3 bc 37 2d 85 e3 31 17 0f cb f7 cc 72 d0 b7 1c
+        29 66 48 b3 a4 d1 0f 41 62 95 d0 80 7a a6 25 ca b2 74 4f d9 ea 8f d2 23 c4 25 37 02 98 28 bd 16
+        be 02 54 6f 13 0f d2 e3 3b 93 6d 26 76 e0 8a ed 1b 73 31 8b 75 0a 01 67 d0
+    ").unwrap();
+    let expected = hex_decode("
+        6b c3 a0 66 56 84 29 30 a2 47 e3 0d 58 64 b4 d8 19 23 6b a7 c6 89 65 86 2a d7 db c4 e2 4a f2 8e
+        86 bb 53 1f 03 35 8b e5 fb 74 77 7c 60 86 f8 50 ca ef 89 3f 0d 6f cc 2d 0c 91 ec 01 36 93 b4 ea
+        00 b8 0c d4 9a ac 4e cb 5f 89 11 af e5 39 ad a4 a8 f3 82 3d 1d 13 e4 72 d1 49 05 47 c6 59 c7 61
+        7f 3d 24 08 7d db 6f 2b 72 09 61 67 fc 09 7c ab 18 e9 a4 58 fc b6 34 cd ce 8e e3 58 94 c4 84 d7
+    ").unwrap();
+
+    let signature = scheme.sign(&key, &message).unwrap();
+    assert_eq!(signature, expected, "RSASSA-PKCS1-v1_5 failed signing");
+    assert_eq!(scheme.verify(&key, &message, &signature), Ok(()));
+}
+
+#[test]
+// RFC 8017 RSASSA-PSS test vectors (pss-vect.txt, example 1.1)
+fn test_pss_rfc8017_vector() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_signature_example_key;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_signature_example_key();
+    let scheme = PSS::<SHA1>::new(20);
+    let message = hex_decode("
+        cd c8 7d a2 23 d7 86 df 3b 45 e0 bb bc 72 13 26 d1 ee 2a f8 06 cc 31 54 75 cc 6f 0d 9c 66 e1 b6
+        23 71 d4 5c e2 39 2e 1a c9 28 44 c3 10 10 2f 15 6a 0d 8d 52 c1 f4 c4 0b a3 aa 65 09 57 86 cb 76
+        97 57 a6 56 3b a9 58 fe d0 bc c9 84 e8 b5 17 a3 d5 f5 15 b2 3b 8a 41 e7 4a a8 67 69 3f 90 df b0
+        61 a6 e8 6d fa ae e6 44 72 c0 0e 5f 20 94 57 29 cb eb e7 7f 06 ce 78 e0 8f 40 98 fb a4 1f 9d 61
+        93 c0 31 7e 8b 60 d4 b6 08 4a cb 42 d2 9e 38 08 a3 bc 37 2d 85 e3 31 17 0f cb f7 cc 72 d0 b7 1c
+        29 66 48 b3 a4 d1 0f 41 62 95 d0 80 7a a6 25 ca b2 74 4f d9 ea 8f d2 23 c4 25 37 02 98 28 bd 16
+        be 02 54 6f 13 0f d2 e3 3b 93 6d 26 76 e0 8a ed 1b 73 31 8b 75 0a 01 67 d0
+    ").unwrap();
+    let salt = hex_decode("de e9 59 c7 e0 64 11 36 14 20 ff 80 18 5e d5 7f 3e 67 76 af").unwrap();
+    let expected = hex_decode("
+        90 74 30 8f b5 98 e9 70 1b 22 94 38 8e 52 f9 71 fa ac 2b 60 a5 14 5a f1 85 df 52 87 b5 ed 28 87
+        e5 7c e7 fd 44 dc 86 34 e4 07 c8 e0 e4 36 0b c2 26 f3 ec 22 7f 9d 9e 54 63 8e 8d 31 f5 05 12 15
+        df 6e bb 9c 2f 95 79 aa 77 59 8a 38 f9 14 b5 b9 c1 bd 83 c4 e2 f9 f3 82 a0 d0 aa 35 42 ff ee 65
+        98 4a 60 1b c6 9e b2 8d eb 27 dc a1 2c 82 c2 d4 c3 f6 6c d5 00 f1 ff 2b 99 4d 8a 4e 30 cb b3 3c
+    ").unwrap();
+
+    let signature = scheme.sign_with_salt(&key, &message, &salt).unwrap();
+    assert_eq!(signature, expected, "RSASSA-PSS failed signing");
+    assert_eq!(scheme.verify(&key, &message, &signature), Ok(()));
+}
+
+#[test]
+// signature produced by pyca/cryptography (OpenSSL backend) with the same key
+fn test_pkcs1v15_known_signature() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_example_key();
+    let scheme = PKCS1v15Sign::<SHA1>::new();
+    let message = b"cryptography playground";
+    let expected = hex_decode("
+        6365919cad33998f96b804d13d295c27941a5837360d30b7ba7aae745be0ec96
+        d285f16ad938d7a2eb33c02b8ab4312d17a40edf0fce514a4b99d460212cd669
+        80636b9d78f676c7d8ec7a09c345a92641b239668e2a27326130429d0a2e6ee0
+        8c6b2376954a84dc512ba8d46b2984342e068454a6b089540e2abfa9ff0c065c
+    ").unwrap();
+
+    let signature = scheme.sign(&key, message).unwrap();
+    assert_eq!(signature, expected, "RSASSA-PKCS1-v1_5 failed signing");
+    assert_eq!(scheme.verify(&key, message, &signature), Ok(()));
+}
+
+#[test]
+// signatures produced by pyca/cryptography (OpenSSL backend) with the same key
+fn test_pkcs1v15_sha2_sha3_known_signatures() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::utils::hex_decode;
+
+    fn check<H: DigestInfo>(key: &RSA, expected: &str) {
+        let scheme = PKCS1v15Sign::<H>::new();
+        let expected = hex_decode(expected).unwrap();
+        let signature = scheme.sign(key, b"cryptography playground").unwrap();
+        assert_eq!(signature, expected);
+        assert_eq!(scheme.verify(key, b"cryptography playground", &signature), Ok(()));
+    }
+
+    let key = rfc8017_example_key();
+    check::<SHA256>(&key, "
+        4ec1f4aee9ce5df4f55be42385fa80f7345285f2935f08680b4a8897357eac25
+        e7610f514792d2355f64a1bbda993d77b80931126d777cfd04634648dd486f46
+        79e427d44e742a42141c7692baebe5cbc58923aede2fbd4de42d8ecb3f6756c6
+        3e12bbf080ec547f1cdf64377b96972a723ffd92791d240318b76aecb50df2db
+    ");
+    check::<SHA512>(&key, "
+        18374687635ce293d6e1f6ef4a45a077bf7aa358601f2f1fa43eaa38886effa8
+        21c509b73118d31747952bcaa9d304ec78aea24359c7fe4cdd9b26432a6e1d54
+        49f8aea4a623b2d8c0a0c7e24b5117b92e7acb1100888984f0cd424b04d9ebf6
+        a6b088782db32ef4df84176dde5fec114223eac8016f52cfab813218b00d330c
+    ");
+    check::<SHA512_256>(&key, "
+        614a2aba23b365eafc8f63f3d045ad01378a541a8366cbec877d93ba6344f3c1
+        79d34508c02714fe67af7c236441ad9c7cee8043122459cdebb6bafb7613bc00
+        8798059bfce25ec6624b00c126e71b1383c979c76d18479a290559483e342baf
+        fc214ae49e959aa5672cebc20056cbc752a607ef9e252ccb1e5e1057fcb673fd
+    ");
+    check::<SHA3_256>(&key, "
+        467c004d0fa7c17a8132ca8574d89469799bcc7104b3a9c83cb7dcc99220987a
+        655a8d1f2467584c0aba7a9d049a46fdcaf3aacc7a8318f3ed93499a195675a5
+        b040197e9f4eb4df6c27bd872a7143488c840c95c3bf21a5cae116d92122966f
+        d7afcf0905359f99b4ad7ec5910788d5c6b3feb04b659869c4f9af5869516cbe
+    ");
+
+    // a PSS signature with SHA-256 and MGF1-SHA-256
+    let signature = hex_decode("
+        21777857856af97366588dedc52e1b5f52c3c5abc0b138e55c83b869c0b01758
+        a58a4e0a582e10b9a84bc4db72e9a411f5c74190370e8dcdb2e51cae95bd257b
+        cf92c054cc842ea3b867f42aa5a93024af04546a74b5f8105164444501fbc685
+        55d8af2fdf5f88cf771e282bf7e55f62441b36e11c4f33a269c042dd87d93bb4
+    ").unwrap();
+    assert_eq!(PSS::<SHA256>::new(32).verify(&key, b"cryptography playground", &signature), Ok(()));
+}
+
+#[test]
+// signature produced by pyca/cryptography (OpenSSL backend) with the same key
+fn test_pss_known_signature() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+    use crate::utils::hex_decode;
+
+    let key = rfc8017_example_key();
+    let scheme = PSS::<SHA1>::new(20);
+    let message = b"cryptography playground";
+    let signature = hex_decode("
+        a1ae25c89cba8c806adf6cddac3e7e939119eb6e7470d39e78f8782e4aae5ce6
+        1976161669987d6b2f623275b878c38d322f4d6c86b4cb7c317462c1470b22de
+        f8e1190fb81752540f54ae1ff0fa0c22c722b3179c8a6329e29455934273b023
+        97340d3e7ae3d37b852e097bc4cd3ef3aeab09e41910facae7fd10fc18767547
+    ").unwrap();
+
+    assert_eq!(scheme.verify(&key, message, &signature), Ok(()));
+
+    // the salt length is part of the parameters
+    assert_eq!(PSS::<SHA1>::new(32).verify(&key, message, &signature), Err(Error::InvalidSignature));
+
+    // random salts make signatures probabilistic
+    let s1 = scheme.sign(&key, message).unwrap();
+    let s2 = scheme.sign(&key, message).unwrap();
+    assert_ne!(s1, s2);
+    assert_eq!(scheme.verify(&key, message, &s1), Ok(()));
+    assert_eq!(scheme.verify(&key, message, &s2), Ok(()));
+
+    // a zero length salt is deterministic
+    let scheme = PSS::<SHA1>::new(0);
+    let signature = scheme.sign(&key, message).unwrap();
+    assert_eq!(signature, scheme.sign_with_salt(&key, message, &[]).unwrap());
+    assert_eq!(scheme.verify(&key, message, &signature), Ok(()));
+}
+
+#[cfg(test)]
+// Wycheproof-style edge cases, every one of them must be rejected by verify
+fn check_rejects_malformed(key: &RSA, signature: &[u8], verify: impl Fn(&[u8], &[u8]) -> Result<()>) {
+    let message = b"cryptography playground";
+    assert_eq!(verify(message, signature), Ok(()));
+
+    // different message
+    assert_eq!(verify(b"cryptography playground!", signature), Err(Error::InvalidSignature));
+
+    // flipped bits
+    for i in [0, 1, 63, 126, 127] {
+        let mut tampered = signature.to_vec();
+        tampered[i] ^= 0x01;
+        assert_eq!(verify(message, &tampered), Err(Error::InvalidSignature));
+    }
+
+    // leading zero added or removed, empty signature
+    assert_eq!(verify(message, &[&[0u8][..], signature].concat()), Err(Error::InvalidSignature));
+    assert_eq!(verify(message, &signature[1..]), Err(Error::InvalidSignature));
+    assert_eq!(verify(message, &[]), Err(Error::InvalidSignature));
+
+    // s >= n and trivial signatures (0, 1, n - 1)
+    assert_eq!(verify(message, &key.n().to_bytes_be()), Err(Error::InvalidSignature));
+    assert_eq!(verify(message, &[0xFF; 128]), Err(Error::InvalidSignature));
+    assert_eq!(verify(message, &[0u8; 128]), Err(Error::InvalidSignature));
+    let mut one = [0u8; 128];
+    one[127] = 1;
+    assert_eq!(verify(message, &one), Err(Error::InvalidSignature));
+    let n_minus_one = i2osp(&(key.n() - 1u32), 128).unwrap();
+    assert_eq!(verify(message, &n_minus_one), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn test_signature_rejects_malformed() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_example_key;
+
+    let key = rfc8017_example_key();
+    let message = b"cryptography playground";
+
+    let pkcs1 = PKCS1v15Sign::<SHA1>::new();
+    let signature = pkcs1.sign(&key, message).unwrap();
+    check_rejects_malformed(&key, &signature, |m, s| pkcs1.verify(&key, m, s));
+
+    let pss = PSS::<SHA1>::new(20);
+    let signature = pss.sign(&key, message).unwrap();
+    check_rejects_malformed(&key, &signature, |m, s| pss.verify(&key, m, s));
+}
+
+#[test]
+// the malformed PKCS#1 v1.5 encodings of the Wycheproof rsa_signature tests, signed with the
+// private key: s^e mod n gives back each of them, and verify must reject every one
+fn test_pkcs1v15_rejects_wycheproof_encodings() {
+    use crate::ciphers::rsa::rsa_utils::rfc8017_signature_example_key;
+
+    let key = rfc8017_signature_example_key();
+    let scheme = PKCS1v15Sign::<SHA1>::new();
+    let message = b"cryptography playground";
+    let k = key.modulus_len();
+
+    // 0x00 || block_type || PS (filled with ps) || 0x00 || t, t right aligned
+    let encode = |block_type: u8, ps: u8, t: &[u8]| {
+        let mut em = vec![0x00, block_type];
+        em.resize(k - t.len() - 1, ps);
+        em.push(0x00);
+        em.extend_from_slice(t);
+        em
+    };
+    let sign = |em: &[u8]| i2osp(&key.sign_message(&os2ip(em)).unwrap(), k).unwrap();
+
+    let digest = SHA1::digest(message);
+    let t = [SHA1::DIGEST_INFO_PREFIX, &digest].concat();
+    assert_eq!(scheme.verify(&key, message, &sign(&encode(0x01, 0xFF, &t))), Ok(()));
+
+    let mut wrong_oid = t.clone();
+    wrong_oid[10] ^= 0x01;
+    let mut long_form = vec![0x30, 0x81, 0x21];
+    long_form.extend_from_slice(&t[2..]);
+    let mut no_null = vec![0x30, 0x1f, 0x30, 0x07];
+    no_null.extend_from_slice(&t[4..11]);
+    no_null.extend_from_slice(&t[13..]);
+    let mut no_separator = encode(0x01, 0xFF, &t);
+    no_separator[k - t.len() - 1] = 0xFF;
+    let sha256_t = [SHA256::DIGEST_INFO_PREFIX, &SHA256::digest(message)].concat();
+
+    let cases = [
+        ("using PKCS#1 encryption padding", encode(0x02, 0xFF, &t)),
+        ("padding with zeros instead of 0xFF", encode(0x01, 0x00, &t)),
+        ("no 0x00 after the padding", no_separator),
+        ("wrong hash OID", encode(0x01, 0xFF, &wrong_oid)),
+        ("digest of another hash", encode(0x01, 0xFF, &sha256_t)),
+        ("length of sequence uses long form", encode(0x01, 0xFF, &long_form)),
+        ("DigestInfo without NULL parameters", encode(0x01, 0xFF, &no_null)),
+        ("appending 0's to the digest", encode(0x01, 0xFF, &[&t[..], &[0x00]].concat())),
+        ("truncated digest", encode(0x01, 0xFF, &t[..t.len() - 1])),
+    ];
+    for (comment, em) in cases {
+        assert_eq!(scheme.verify(&key, message, &sign(&em)), Err(Error::InvalidSignature), "{}", comment);
+    }
+}
+
+#[test]
+// RSA::new accepts any modulus, a zero one must be an error and not an underflow
+fn test_pss_zero_modulus() {
+    use num_bigint::BigUint;
+
+    let key = RSA::new(BigUint::ZERO, BigUint::from(65537u32), BigUint::from(1u32));
+    let pss = PSS::<SHA1>::new(20);
+    assert!(matches!(pss.sign(&key, b"message"), Err(Error::InvalidParameter(_))));
+    assert_eq!(pss.verify(&key, b"message", &[]), Err(Error::InvalidSignature));
+    assert!(PKCS1v15Sign::<SHA1>::new().sign(&key, b"message").is_err());
+    assert_eq!(PKCS1v15Sign::<SHA1>::new().verify(&key, b"message", &[]), Err(Error::InvalidSignature));
+}
@@ -0,0 +1,244 @@
+/*
+* Salsa20 stream cipher (Bernstein, 2005) and XSalsa20 (Bernstein, 2008)
+*
+* Carachteristics:
+* - 256 bit key, 64 bit nonce, 64 bit block counter
+* - 512 bit state, constants on the diagonal:
+*     c0 k0 k1 k2 / k3 c1 n0 n1 / t0 t1 c2 k4 / k5 k6 k7 c3
+* - 20 rounds (Salsa20/20), reduced round Salsa20/8 and Salsa20/12 for experiments
+* - XSalsa20: 192 bit nonce, the key is first mixed with 128 bits of it by HSalsa20
+*   so random nonces can be used without worrying about collisions
+*/
+
+use crate::{Error, Result, StreamCipher};
+use crate::ciphers::chacha20::{SIGMA, words};
+
+const BLOCK_SIZE: usize = 64;
+
+pub struct Salsa20 {
+    key: [u32; 8],
+    nonce: [u32; 2],
+    // number of rounds: 20, 12 or 8
+    rounds: usize,
+    // byte offset in the keystream
+    position: u64,
+}
+
+impl StreamCipher for Salsa20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) -> Result<()> {
+        // the 64 bit counter only runs out together with the byte offset
+        self.position.checked_add(data.len() as u64).ok_or(Error::CounterOverflow)?;
+
+        let mut done = 0;
+        while done < data.len() {
+            let offset = (self.position % BLOCK_SIZE as u64) as usize;
+            let keystream = self.block(self.position / BLOCK_SIZE as u64);
+
+            let n = (BLOCK_SIZE - offset).min(data.len() - done);
+            for (byte, k) in data[done..done + n].iter_mut().zip(&keystream[offset..]) {
+                *byte ^= k;
+            }
+            done += n;
+            self.position += n as u64;
+        }
+        Ok(())
+    }
+
+    fn seek(&mut self, position: u64) -> Result<()> {
+        self.position = position;
+        Ok(())
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl Salsa20 {
+    // Salsa20/20, key: 32 bytes, nonce: 8 bytes
+    pub fn new(key: &[u8], nonce: &[u8]) -> Result<Self> {
+        Self::with_rounds(key, nonce, 20)
+    }
+
+    // reduced round variants: Salsa20/8 and Salsa20/12
+    pub fn with_rounds(key: &[u8], nonce: &[u8], rounds: usize) -> Result<Self> {
+        if !matches!(rounds, 8 | 12 | 20) {
+            return Err(Error::InvalidParameter("Salsa20 rounds must be 8, 12 or 20"));
+        }
+        let key = words::<8>(key).ok_or(Error::InvalidKeyLength(key.len()))?;
+        let nonce = words::<2>(nonce).ok_or(Error::InvalidParameter("Salsa20 nonce must be 8 bytes"))?;
+        Ok(Self { key, nonce, rounds, position: 0 })
+    }
+
+    // Salsa20 core: the 64 bytes keystream block for the given counter
+    pub fn block(&self, counter: u64) -> [u8; BLOCK_SIZE] {
+        let state = initial_state(&self.key, [self.nonce[0], self.nonce[1], counter as u32, (counter >> 32) as u32]);
+
+        let mut working = state;
+        permute(&mut working, self.rounds);
+
+        let mut out = [0u8; BLOCK_SIZE];
+        for (chunk, (w, s)) in out.chunks_exact_mut(4).zip(working.iter().zip(state)) {
+            chunk.copy_from_slice(&w.wrapping_add(s).to_le_bytes());
+        }
+        out
+    }
+}
+
+// XSalsa20: Salsa20/20 keyed with HSalsa20(key, nonce[..16]) and nonce[16..]
+pub struct XSalsa20(Salsa20);
+
+impl XSalsa20 {
+    // key: 32 bytes, nonce: 24 bytes
+    pub fn new(key: &[u8], nonce: &[u8]) -> Result<Self> {
+        if nonce.len() != 24 {
+            return Err(Error::InvalidParameter("XSalsa20 nonce must be 24 bytes"));
+        }
+        let subkey = hsalsa20(key, &nonce[..16])?;
+        Ok(Self(Salsa20::new(&subkey, &nonce[16..])?))
+    }
+}
+
+impl StreamCipher for XSalsa20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) -> Result<()> {
+        self.0.apply_keystream(data)
+    }
+
+    fn seek(&mut self, position: u64) -> Result<()> {
+        self.0.seek(position)
+    }
+
+    fn position(&self) -> u64 {
+        self.0.position()
+    }
+}
+
+// HSalsa20: 20 rounds over (key, 16 bytes input) without the final addition,
+// outputs the diagonal and the input words as a new 32 bytes key
+pub fn hsalsa20(key: &[u8], input: &[u8]) -> Result<[u8; 32]> {
+    let key = words::<8>(key).ok_or(Error::InvalidKeyLength(key.len()))?;
+    let input = words::<4>(input).ok_or(Error::InvalidParameter("HSalsa20 input must be 16 bytes"))?;
+
+    let mut state = initial_state(&key, input);
+    permute(&mut state, 20);
+
+    let mut out = [0u8; 32];
+    for (chunk, i) in out.chunks_exact_mut(4).zip([0, 5, 10, 15, 6, 7, 8, 9]) {
+        chunk.copy_from_slice(&state[i].to_le_bytes());
+    }
+    Ok(out)
+}
+
+// places constants, key and the 4 words of nonce and counter in the state
+fn initial_state(key: &[u32; 8], input: [u32; 4]) -> [u32; 16] {
+    [
+        SIGMA[0], key[0], key[1], key[2],
+        key[3], SIGMA[1], input[0], input[1],
+        input[2], input[3], SIGMA[2], key[4],
+        key[5], key[6], key[7], SIGMA[3],
+    ]
+}
+
+// quarter round on the words a, b, c, d of the state
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[b] ^= state[a].wrapping_add(state[d]).rotate_left(7);
+    state[c] ^= state[b].wrapping_add(state[a]).rotate_left(9);
+    state[d] ^= state[c].wrapping_add(state[b]).rotate_left(13);
+    state[a] ^= state[d].wrapping_add(state[c]).rotate_left(18);
+}
+
+// rounds / 2 double rounds (column round + row round), without the final addition
+fn permute(state: &mut [u32; 16], rounds: usize) {
+    for _ in 0..rounds / 2 {
+        // column round
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 5, 9, 13, 1);
+        quarter_round(state, 10, 14, 2, 6);
+        quarter_round(state, 15, 3, 7, 11);
+        // row round
+        quarter_round(state, 0, 1, 2, 3);
+        quarter_round(state, 5, 6, 7, 4);
+        quarter_round(state, 10, 11, 8, 9);
+        quarter_round(state, 15, 12, 13, 14);
+    }
+}
+
+#[test]
+// eSTREAM Salsa20/20 256 bit key, set 1 vector 0
+fn test_salsa20_estream_vector() {
+    use crate::utils::hex_encode;
+
+    let mut key = [0u8; 32];
+    key[0] = 0x80;
+    let cipher = Salsa20::new(&key, &[0; 8]).unwrap();
+    assert_eq!(hex_encode(&cipher.block(0)), "\
+        e3be8fdd8beca2e3ea8ef9475b29a6e7003951e1097a5c38d23b7a5fad9f6844\
+        b22c97559e2723c7cbbd3fe4fc8d9a0744652a83e72a9c461876af4d7ef1a117");
+}
+
+#[test]
+// keystreams produced by libsodium (crypto_stream_salsa20, salsa2012, salsa208)
+fn test_salsa20_reduced_rounds() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key: Vec<u8> = (0..32).collect();
+    let nonce = hex_decode("0001020304050607").unwrap();
+    let vectors = [
+        (20, "2ead0f5f185729ced672b3a928e454f72fdb44a87b9cd8d219e4ec14aef9c6bc77bf057f5659d7753848f8d3fe769ca5fdd8057d46326990e5f136e2fcb7bb7c"),
+        (12, "06c9dd540af341e7e77e5d604594247d13accb164c02b45db37d1abdcddb501e7bdf1a99c6ac8ad2d71c14424f03a056acfb41cfbaea8c84881e7fcbf0576c33"),
+        (8, "6f305a9a55da5f8a79a7e372135db532d05c6574de2623a23edb4d955062cbd68d9324c1db60747f6713d9d2f9c446a743ba8351e9c7cc064a114dce38de5c56"),
+    ];
+    for (rounds, expected) in vectors {
+        let mut data = [0u8; 64];
+        Salsa20::with_rounds(&key, &nonce, rounds).unwrap().apply_keystream(&mut data).unwrap();
+        assert_eq!(hex_encode(&data), expected, "Salsa20/{}", rounds);
+    }
+    assert!(matches!(Salsa20::with_rounds(&key, &nonce, 10), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_salsa20_streaming_and_seek() {
+    use crate::utils::hex_encode;
+
+    // the 200 bytes keystream of the libsodium vector above, in pieces
+    let key: Vec<u8> = (0..32).collect();
+    let mut cipher = Salsa20::new(&key, &[0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+    let mut data = [0u8; 200];
+    for chunk in data.chunks_mut(13) {
+        cipher.apply_keystream(chunk).unwrap();
+    }
+    assert_eq!(hex_encode(&data[128..]), "\
+        8740bfe7d23f9c0432c259039c79573aa220c0722b049623f3a19e9cd1b143f2\
+        c36c1afcd419270f66c0789af6a9702d2da4e676e3d5ad4a62234fb3f5c4eab3\
+        80496d4dd3d328cf");
+
+    cipher.seek(150).unwrap();
+    let mut tail = [0u8; 50];
+    cipher.apply_keystream(&mut tail).unwrap();
+    assert_eq!(tail, data[150..]);
+
+    cipher.seek(u64::MAX).unwrap();
+    assert_eq!(cipher.apply_keystream(&mut [0u8; 1]), Err(Error::CounterOverflow));
+}
+
+#[test]
+// XSalsa20 and HSalsa20 outputs produced by libsodium (NaCl secretbox test key)
+fn test_xsalsa20_known_vectors() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key = hex_decode("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389").unwrap();
+    let nonce = hex_decode("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37").unwrap();
+
+    assert_eq!(
+        hex_encode(&hsalsa20(&key, &nonce[..16]).unwrap()),
+        "dc908dda0b9344a953629b733820778880f3ceb421bb61b91cbd4c3e66256ce4"
+    );
+
+    let mut data = [0u8; 64];
+    XSalsa20::new(&key, &nonce).unwrap().apply_keystream(&mut data).unwrap();
+    assert_eq!(hex_encode(&data), "\
+        eea6a7251c1e72916d11c2cb214d3c252539121d8e234e652d651fa4c8cff880\
+        309e645a74e9e0a60d8243acd9177ab51a1beb8d5a2f5d700c093c5e55855796");
+
+    assert!(matches!(XSalsa20::new(&key, &nonce[..8]), Err(Error::InvalidParameter(_))));
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+// every fallible operation of the crate returns this error
+// so that malformed inputs (ciphertexts, keys, ...) never cause a panic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // a block was built from the wrong number of bytes
+    InvalidBlockLength { expected: usize, actual: usize },
+    // the input of a mode is not a multiple of the block size
+    NotBlockAligned { block_size: usize, len: usize },
+    // the key has an unsupported length (in bytes)
+    InvalidKeyLength(usize),
+    // malformed padding found while unpadding
+    InvalidPadding,
+    // the counter of a counter-based mode would wrap around
+    CounterOverflow,
+    // a mode or cipher was configured with invalid parameters
+    InvalidParameter(&'static str),
+    // a number has no inverse modulo n (es: gcd(e, phi(n)) != 1)
+    NoModularInverse,
+    // a message or ciphertext is not smaller than the modulus
+    MessageOutOfRange,
+    // a private key operation produced a wrong result (es: RSA-CRT fault)
+    FaultDetected,
+    // the message is too long for the key or the encoding
+    MessageTooLong,
+    // a ciphertext couldn't be decrypted or decoded
+    // NOTE: deliberately carries no details, to avoid building a decryption oracle
+    DecryptionFailed,
+    // a signature doesn't match the message and the public key
+    InvalidSignature,
+    // a MAC or authentication tag doesn't match the data
+    InvalidTag,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidBlockLength { expected, actual } => {
+                write!(f, "invalid block length: expected {} bytes, got {}", expected, actual)
+            }
+            Error::NotBlockAligned { block_size, len } => {
+                write!(f, "input of {} bytes is not a multiple of the block size ({})", len, block_size)
+            }
+            Error::InvalidKeyLength(len) => write!(f, "invalid key length: {} bytes", len),
+            Error::InvalidPadding => write!(f, "malformed padding"),
+            Error::CounterOverflow => write!(f, "counter overflow: input too long for the counter size"),
+            Error::InvalidParameter(msg) => write!(f, "invalid parameter: {}", msg),
+            Error::NoModularInverse => write!(f, "no modular inverse exists"),
+            Error::MessageOutOfRange => write!(f, "message is not smaller than the modulus"),
+            Error::FaultDetected => write!(f, "fault detected in a private key operation"),
+            Error::MessageTooLong => write!(f, "message too long"),
+            Error::DecryptionFailed => write!(f, "decryption error"),
+            Error::InvalidSignature => write!(f, "invalid signature"),
+            Error::InvalidTag => write!(f, "authentication tag mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// lets the io adapters (CipherWriter, CipherReader) return crate errors
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+// makes sure that input is a multiple of block_size
+pub(crate) fn check_block_aligned(input: &[u8], block_size: usize) -> Result<()> {
+    if !input.len().is_multiple_of(block_size) {
+        return Err(Error::NotBlockAligned { block_size, len: input.len() });
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+/*
+* MD5 as defined by RFC 1321
+*
+* Carachteristics:
+* - 512 bit blocks, 128 bit digest
+* - Merkle-Damgard construction, words and length are little-endian
+* - 4 rounds of 16 steps, each one with its own boolean function
+*
+* NOTE: MD5 is broken (collisions in seconds), it's here for teaching purposes only
+*/
+
+use crate::hash::Hash;
+use crate::hash::merkle_damgard::{Compression, MerkleDamgard, MerkleDamgardHash};
+
+// initial hash value (A, B, C, D)
+const IV: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+// T[i] = floor(2^32 * |sin(i + 1)|)
+const T: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+// left rotations of every step, 4 per round
+const S: [[u32; 4]; 4] = [[7, 12, 17, 22], [5, 9, 14, 20], [4, 11, 16, 23], [6, 10, 15, 21]];
+
+// MD5 compression function
+#[derive(Clone)]
+pub struct MD5Compression;
+
+#[derive(Clone)]
+pub struct MD5(MerkleDamgard<MD5Compression>);
+
+impl Hash for MD5 {
+    const OUTPUT_SIZE: usize = 16;
+    const BLOCK_SIZE: usize = MD5Compression::BLOCK_SIZE;
+
+    fn new() -> Self {
+        Self(MerkleDamgard::new(IV))
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        self.0.finalize()
+    }
+}
+
+impl MerkleDamgardHash for MD5 {
+    fn from_digest(digest: &[u8], len: u64) -> Option<Self> {
+        let state = MD5Compression::state_from_bytes(digest)?;
+        let processed = len as u128 + Self::padding(len).len() as u128;
+        Some(Self(MerkleDamgard::resume(state, processed)))
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        MerkleDamgard::<MD5Compression>::padding(len as u128)
+    }
+}
+
+impl Compression for MD5Compression {
+    type State = [u32; 4];
+    const BLOCK_SIZE: usize = 64;
+    const LENGTH_SIZE: usize = 8;
+    const BIG_ENDIAN: bool = false;
+
+    // processes a single 64 bytes block
+    fn compress(state: &mut [u32; 4], block: &[u8]) {
+        let mut x = [0u32; 16];
+        for (xi, word) in x.iter_mut().zip(block.chunks_exact(4)) {
+            *xi = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = *state;
+        for (i, &t) in T.iter().enumerate() {
+            // boolean function and message word of each round
+            let (f, k) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((b & d) | (c & !d), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let tmp = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(
+                a.wrapping_add(f).wrapping_add(t).wrapping_add(x[k]).rotate_left(S[i / 16][i % 4]),
+            );
+            a = tmp;
+        }
+
+        for (h, v) in state.iter_mut().zip([a, b, c, d]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    fn state_to_bytes(state: &[u32; 4]) -> Vec<u8> {
+        state.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    fn state_from_bytes(bytes: &[u8]) -> Option<[u32; 4]> {
+        if bytes.len() != 16 {
+            return None;
+        }
+        let mut state = [0u32; 4];
+        for (w, chunk) in state.iter_mut().zip(bytes.chunks_exact(4)) {
+            *w = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(state)
+    }
+}
+
+#[test]
+// RFC 1321 appendix A.5 test suite
+fn test_md5_known_vectors() {
+    use crate::utils::hex_encode;
+
+    let vectors: [(&[u8], &str); 7] = [
+        (b"", "d41d8cd98f00b204e9800998ecf8427e"),
+        (b"a", "0cc175b9c0f1b6a831c399e269772661"),
+        (b"abc", "900150983cd24fb0d6963f7d28e17f72"),
+        (b"message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        (b"abcdefghijklmnopqrstuvwxyz", "c3fcd3d76192e4007dfb496cca67e13b"),
+        (b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "d174ab98d277d9f5a5611c2c9f419d9f"),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ];
+    for (message, expected) in vectors {
+        assert_eq!(hex_encode(&MD5::digest(message)), expected);
+    }
+}
+
+#[test]
+fn test_md5_streaming() {
+    let message = b"12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+    let mut hasher = MD5::new();
+    for chunk in message.chunks(7) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finalize(), MD5::digest(message));
+}
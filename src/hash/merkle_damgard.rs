@@ -0,0 +1,119 @@
+/*
+* Merkle-Damgard construction
+*
+*   H(0) = IV
+*   H(i) = f(H(i-1), M(i))        digest = H(n)
+*
+* the message is padded with 0x80, zeros and its length in bits (MD strengthening)
+* NOTE: the digest IS the final chaining value, so anyone knowing H(M) and len(M)
+* can keep hashing from there: H(M || pad || X) without knowing M (length extension)
+*/
+
+use crate::hash::Hash;
+use crate::utils::BlockBuffer;
+
+// compression function f of a Merkle-Damgard hash (es: SHA-256)
+pub trait Compression {
+    // chaining value
+    type State: Clone;
+
+    // size of the message blocks in bytes
+    const BLOCK_SIZE: usize;
+
+    // size in bytes of the length appended by the padding
+    const LENGTH_SIZE: usize;
+
+    // MD5 encodes words and length little-endian, SHA-1 and SHA-2 big-endian
+    const BIG_ENDIAN: bool;
+
+    // processes a single block
+    fn compress(state: &mut Self::State, block: &[u8]);
+
+    // serializes the chaining value (the untruncated digest)
+    fn state_to_bytes(state: &Self::State) -> Vec<u8>;
+
+    // inverse of state_to_bytes, None if bytes has the wrong length
+    fn state_from_bytes(bytes: &[u8]) -> Option<Self::State>;
+}
+
+// streaming Merkle-Damgard hash over the compression function C
+#[derive(Clone)]
+pub struct MerkleDamgard<C: Compression> {
+    // current chaining value
+    state: C::State,
+    // bytes not yet processed (always less than a block)
+    buffer: BlockBuffer,
+    // total number of bytes absorbed
+    len: u128,
+}
+
+impl<C: Compression> MerkleDamgard<C> {
+    pub fn new(iv: C::State) -> Self {
+        Self::resume(iv, 0)
+    }
+
+    // restarts from a chaining value after len bytes (a multiple of the block size)
+    // have already been processed
+    pub fn resume(state: C::State, len: u128) -> Self {
+        Self { state, buffer: BlockBuffer::new(C::BLOCK_SIZE), len }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u128;
+        self.buffer.update(data, |block| C::compress(&mut self.state, block));
+    }
+
+    // pads the remaining data and returns the final chaining value
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = Self::padding(self.len);
+        self.update(&padding);
+        C::state_to_bytes(&self.state)
+    }
+
+    // padding appended to a message of len bytes:
+    // 0x80, zeros up to BLOCK_SIZE - LENGTH_SIZE (mod BLOCK_SIZE) and the length in bits
+    pub fn padding(len: u128) -> Vec<u8> {
+        let used = (len % C::BLOCK_SIZE as u128) as usize;
+        let zeros = (2 * C::BLOCK_SIZE - C::LENGTH_SIZE - used - 1) % C::BLOCK_SIZE;
+        let mut padding = vec![0x80u8];
+        padding.resize(1 + zeros, 0);
+
+        let bit_len = len.wrapping_mul(8);
+        if C::BIG_ENDIAN {
+            padding.extend_from_slice(&bit_len.to_be_bytes()[16 - C::LENGTH_SIZE..]);
+        } else {
+            padding.extend_from_slice(&bit_len.to_le_bytes()[..C::LENGTH_SIZE]);
+        }
+        padding
+    }
+}
+
+// Merkle-Damgard hashes whose digest is the whole chaining value (es: MD5, SHA-256)
+// and can therefore be resumed from a digest
+pub trait MerkleDamgardHash: Hash + Sized {
+    // resumes hashing from digest = H(M) where M was len bytes long
+    // (the padding of M is implicitly part of the processed data)
+    fn from_digest(digest: &[u8], len: u64) -> Option<Self>;
+
+    // padding appended to a message of len bytes
+    fn padding(len: u64) -> Vec<u8>;
+}
+
+#[test]
+// padded messages are always a multiple of the block size, ending with the length in bits
+fn test_merkle_damgard_padding() {
+    use crate::hash::md5::MD5Compression;
+    use crate::hash::sha2::SHA512Compression;
+
+    for len in [0u128, 1, 55, 56, 63, 64, 119] {
+        let padding = MerkleDamgard::<MD5Compression>::padding(len);
+        assert!((len + padding.len() as u128).is_multiple_of(64));
+        assert!(padding.len() >= 9 && padding.len() <= 72);
+        assert_eq!(padding[0], 0x80);
+        assert_eq!(padding[padding.len() - 8..], (8 * len as u64).to_le_bytes());
+
+        let padding = MerkleDamgard::<SHA512Compression>::padding(len);
+        assert!((len + padding.len() as u128).is_multiple_of(128));
+        assert_eq!(padding[padding.len() - 16..], (8 * len).to_be_bytes());
+    }
+}
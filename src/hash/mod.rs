@@ -0,0 +1,90 @@
+pub mod merkle_damgard;
+pub mod md5;
+pub mod sha1;
+pub mod sha2;
+pub mod sha3;
+
+pub use merkle_damgard::MerkleDamgardHash;
+pub use md5::MD5;
+pub use sha1::SHA1;
+pub use sha2::{SHA224, SHA256, SHA384, SHA512, SHA512_256};
+pub use sha3::{SHA3_224, SHA3_256, SHA3_384, SHA3_512, SHAKE128, SHAKE256};
+
+// general trait for a cryptographic hash function
+// with a streaming interface (es: SHA-1)
+pub trait Hash {
+    // size of the digest in bytes
+    const OUTPUT_SIZE: usize;
+
+    // size of the internal block in bytes (needed by HMAC)
+    const BLOCK_SIZE: usize;
+
+    // initialize a new hashing state
+    fn new() -> Self;
+
+    // absorbs some more data
+    fn update(&mut self, data: &[u8]);
+
+    // pads the remaining data and outputs the digest
+    fn finalize(self) -> Vec<u8>;
+
+    // one shot hashing of data
+    fn digest(data: &[u8]) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+// hash functions with an arbitrary output length (es: SHAKE128)
+pub trait ExtendableOutput: Hash {
+    // pads the remaining data and squeezes len bytes of output
+    fn finalize_xof(self, len: usize) -> Vec<u8>;
+}
+
+// Mask Generation Function MGF1 (RFC 8017 appendix B.2.1)
+// stretches seed into len bytes as H(seed || 0) || H(seed || 1) || ...
+pub fn mgf1<H: Hash>(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + H::OUTPUT_SIZE);
+    let mut counter = 0u32;
+    while out.len() < len {
+        let mut hasher = H::new();
+        hasher.update(seed);
+        hasher.update(&counter.to_be_bytes());
+        out.extend(hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+#[cfg(test)]
+// checks the "", "abc", 448 and 896 bit messages (FIPS 180-4 examples), one million 'a'
+// and a Len = 8 entry of the CAVP ShortMsg file, shared by the SHA-2 and SHA-3 tests
+pub(crate) fn check_known_vectors<H: Hash>(expected: [&str; 5], cavp: (&str, &str)) {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let messages: [&[u8]; 4] = [
+        b"",
+        b"abc",
+        b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+        b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+    ];
+    for (message, digest) in messages.iter().zip(expected) {
+        assert_eq!(hex_encode(&H::digest(message)), digest);
+    }
+
+    // one million 'a' fed in uneven chunks
+    let mut hasher = H::new();
+    let data = vec![b'a'; 1_000_000];
+    for chunk in data.chunks(333) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hex_encode(&hasher.finalize()), expected[4]);
+
+    let (msg, md) = cavp;
+    assert_eq!(hex_encode(&H::digest(&hex_decode(msg).unwrap())), md);
+}
@@ -0,0 +1,152 @@
+/*
+* SHA-1 as defined by FIPS 180-4
+*
+* Carachteristics:
+* - 512 bit blocks, 160 bit digest
+* - Merkle-Damgard construction over a compression function of 80 steps
+*
+* NOTE: SHA-1 is broken (practical collisions since 2017), it's here for
+* compatibility with old standards and test vectors (es: RSA-OAEP)
+*/
+
+use crate::hash::Hash;
+use crate::hash::merkle_damgard::{Compression, MerkleDamgard, MerkleDamgardHash};
+
+// initial hash value H(0)
+const H0: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+// SHA-1 compression function
+#[derive(Clone)]
+pub struct SHA1Compression;
+
+#[derive(Clone)]
+pub struct SHA1(MerkleDamgard<SHA1Compression>);
+
+impl Hash for SHA1 {
+    const OUTPUT_SIZE: usize = 20;
+    const BLOCK_SIZE: usize = SHA1Compression::BLOCK_SIZE;
+
+    fn new() -> Self {
+        Self(MerkleDamgard::new(H0))
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        self.0.finalize()
+    }
+}
+
+impl MerkleDamgardHash for SHA1 {
+    fn from_digest(digest: &[u8], len: u64) -> Option<Self> {
+        let state = SHA1Compression::state_from_bytes(digest)?;
+        let processed = len as u128 + Self::padding(len).len() as u128;
+        Some(Self(MerkleDamgard::resume(state, processed)))
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        MerkleDamgard::<SHA1Compression>::padding(len as u128)
+    }
+}
+
+impl Compression for SHA1Compression {
+    type State = [u32; 5];
+    const BLOCK_SIZE: usize = 64;
+    const LENGTH_SIZE: usize = 8;
+    const BIG_ENDIAN: bool = true;
+
+    // processes a single 64 bytes block
+    fn compress(state: &mut [u32; 5], block: &[u8]) {
+        // 1. prepare the message schedule
+        let mut w = [0u32; 80];
+        for (t, word) in block.chunks_exact(4).enumerate() {
+            w[t] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for t in 16..80 {
+            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+        }
+
+        // 2. 80 steps, 4 rounds of 20 steps with different functions and constants
+        let [mut a, mut b, mut c, mut d, mut e] = *state;
+        for (t, &wt) in w.iter().enumerate() {
+            let (f, k) = match t {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let tmp = a.rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(wt);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = tmp;
+        }
+
+        // 3. add the compressed chunk to the current hash value
+        for (h, v) in state.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    fn state_to_bytes(state: &[u32; 5]) -> Vec<u8> {
+        state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+
+    fn state_from_bytes(bytes: &[u8]) -> Option<[u32; 5]> {
+        if bytes.len() != 20 {
+            return None;
+        }
+        let mut state = [0u32; 5];
+        for (w, chunk) in state.iter_mut().zip(bytes.chunks_exact(4)) {
+            *w = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(state)
+    }
+}
+
+#[test]
+// FIPS 180-4 examples
+fn test_sha1_known_vectors() {
+    use crate::utils::hex_encode;
+
+    let vectors: [(&[u8], &str); 3] = [
+        (b"", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+        (b"abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+        (
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+        ),
+    ];
+    for (message, expected) in vectors {
+        assert_eq!(hex_encode(&SHA1::digest(message)), expected);
+    }
+}
+
+#[test]
+// CAVP SHA1ShortMsg entries (Len = 8 and Len = 16)
+fn test_sha1_cavp_short_msg() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    assert_eq!(hex_encode(&SHA1::digest(&hex_decode("36").unwrap())), "c1dfd96eea8cc2b62785275bca38ac261256e278");
+    assert_eq!(hex_encode(&SHA1::digest(&hex_decode("195a").unwrap())), "0a1c2d555bbe431ad6288af5a54f93e0449c9232");
+}
+
+#[test]
+fn test_sha1_streaming() {
+    use crate::utils::hex_encode;
+
+    // one million 'a' fed in uneven chunks
+    let mut hasher = SHA1::new();
+    let data = vec![b'a'; 1_000_000];
+    for chunk in data.chunks(333) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hex_encode(&hasher.finalize()), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+}
@@ -0,0 +1,314 @@
+/*
+* SHA-2 family as defined by FIPS 180-4
+*
+* Carachteristics:
+* - SHA-224/256: 512 bit blocks, 32 bit words, 64 steps
+* - SHA-384/512/512-256: 1024 bit blocks, 64 bit words, 80 steps
+* - Merkle-Damgard construction, the truncated variants only differ
+*   from the full ones by the initial hash value and the output length
+*/
+
+use crate::hash::Hash;
+use crate::hash::merkle_damgard::{Compression, MerkleDamgard, MerkleDamgardHash};
+
+// round constants, first 32 bits of the fractional parts of the cube roots of the first 64 primes
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// round constants, first 64 bits of the fractional parts of the cube roots of the first 80 primes
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+// initial hash values H(0) of every variant
+const H224: [u32; 8] = [0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4];
+const H256: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+const H384: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+const H512: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+const H512_256: [u64; 8] = [
+    0x22312194fc2bf72c, 0x9f555fa3c84c64c2, 0x2393b86b6f53b151, 0x963877195940eabd,
+    0x96283ee2a88effe3, 0xbe5e1e2553863992, 0x2b0199fc2c85b8aa, 0x0eb72ddc81c52ca2,
+];
+
+// SHA-224 and SHA-256 compression function
+#[derive(Clone)]
+pub struct SHA256Compression;
+
+// SHA-384, SHA-512 and SHA-512/256 compression function
+#[derive(Clone)]
+pub struct SHA512Compression;
+
+impl Compression for SHA256Compression {
+    type State = [u32; 8];
+    const BLOCK_SIZE: usize = 64;
+    const LENGTH_SIZE: usize = 8;
+    const BIG_ENDIAN: bool = true;
+
+    // processes a single 64 bytes block
+    fn compress(state: &mut [u32; 8], block: &[u8]) {
+        // 1. prepare the message schedule
+        let mut w = [0u32; 64];
+        for (t, word) in block.chunks_exact(4).enumerate() {
+            w[t] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for t in 16..64 {
+            let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+            let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+            w[t] = w[t - 16].wrapping_add(s0).wrapping_add(w[t - 7]).wrapping_add(s1);
+        }
+
+        // 2. 64 steps
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&wt, &kt) in w.iter().zip(K256.iter()) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(kt).wrapping_add(wt);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        // 3. add the compressed chunk to the current hash value
+        for (hv, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *hv = hv.wrapping_add(v);
+        }
+    }
+
+    fn state_to_bytes(state: &[u32; 8]) -> Vec<u8> {
+        state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+
+    fn state_from_bytes(bytes: &[u8]) -> Option<[u32; 8]> {
+        if bytes.len() != 32 {
+            return None;
+        }
+        let mut state = [0u32; 8];
+        for (w, chunk) in state.iter_mut().zip(bytes.chunks_exact(4)) {
+            *w = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(state)
+    }
+}
+
+impl Compression for SHA512Compression {
+    type State = [u64; 8];
+    const BLOCK_SIZE: usize = 128;
+    const LENGTH_SIZE: usize = 16;
+    const BIG_ENDIAN: bool = true;
+
+    // processes a single 128 bytes block
+    fn compress(state: &mut [u64; 8], block: &[u8]) {
+        // 1. prepare the message schedule
+        let mut w = [0u64; 80];
+        for (t, word) in block.chunks_exact(8).enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(word);
+            w[t] = u64::from_be_bytes(bytes);
+        }
+        for t in 16..80 {
+            let s0 = w[t - 15].rotate_right(1) ^ w[t - 15].rotate_right(8) ^ (w[t - 15] >> 7);
+            let s1 = w[t - 2].rotate_right(19) ^ w[t - 2].rotate_right(61) ^ (w[t - 2] >> 6);
+            w[t] = w[t - 16].wrapping_add(s0).wrapping_add(w[t - 7]).wrapping_add(s1);
+        }
+
+        // 2. 80 steps
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (&wt, &kt) in w.iter().zip(K512.iter()) {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(kt).wrapping_add(wt);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        // 3. add the compressed chunk to the current hash value
+        for (hv, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *hv = hv.wrapping_add(v);
+        }
+    }
+
+    fn state_to_bytes(state: &[u64; 8]) -> Vec<u8> {
+        state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+
+    fn state_from_bytes(bytes: &[u8]) -> Option<[u64; 8]> {
+        if bytes.len() != 64 {
+            return None;
+        }
+        let mut state = [0u64; 8];
+        for (w, chunk) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            *w = u64::from_be_bytes(word);
+        }
+        Some(state)
+    }
+}
+
+// defines a SHA-2 variant on top of one of the two compression functions
+macro_rules! sha2_variant {
+    ($name:ident, $compression:ident, $iv:expr, $output_size:expr) => {
+        #[derive(Clone)]
+        pub struct $name(MerkleDamgard<$compression>);
+
+        impl Hash for $name {
+            const OUTPUT_SIZE: usize = $output_size;
+            const BLOCK_SIZE: usize = $compression::BLOCK_SIZE;
+
+            fn new() -> Self {
+                Self(MerkleDamgard::new($iv))
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                let mut digest = self.0.finalize();
+                digest.truncate(Self::OUTPUT_SIZE);
+                digest
+            }
+        }
+    };
+}
+
+// the untruncated variants can be resumed from their digest
+macro_rules! sha2_resumable {
+    ($name:ident, $compression:ident) => {
+        impl MerkleDamgardHash for $name {
+            fn from_digest(digest: &[u8], len: u64) -> Option<Self> {
+                let state = $compression::state_from_bytes(digest)?;
+                let processed = len as u128 + Self::padding(len).len() as u128;
+                Some(Self(MerkleDamgard::resume(state, processed)))
+            }
+
+            fn padding(len: u64) -> Vec<u8> {
+                MerkleDamgard::<$compression>::padding(len as u128)
+            }
+        }
+    };
+}
+
+sha2_variant!(SHA224, SHA256Compression, H224, 28);
+sha2_variant!(SHA256, SHA256Compression, H256, 32);
+sha2_variant!(SHA384, SHA512Compression, H384, 48);
+sha2_variant!(SHA512, SHA512Compression, H512, 64);
+sha2_variant!(SHA512_256, SHA512Compression, H512_256, 32);
+
+sha2_resumable!(SHA256, SHA256Compression);
+sha2_resumable!(SHA512, SHA512Compression);
+
+#[test]
+fn test_sha224_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA224>([
+        "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+        "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+        "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+        "c97ca9a559850ce97a04a96def6d99a9e0e0e2ab14e6b8df265fc0b3",
+        "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67",
+    ], ("84", "3cd36921df5d6963e73739cf4d20211e2d8877c19cff087ade9d0e3a"));
+}
+
+#[test]
+fn test_sha256_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA256>([
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+    ], ("d3", "28969cdfa74a12c82f3bad960b0b000aca2ac329deea5c2328ebc6f2ba9802c1"));
+}
+
+#[test]
+fn test_sha384_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA384>([
+        "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+        "3391fdddfc8dc7393707a65b1b4709397cf8b1d162af05abfe8f450de5f36bc6b0455a8520bc4e6f5fe95b1fe3c8452b",
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039",
+        "9d0e1809716474cb086e834e310a4a1ced149e9c00f248527972cec5704c2a5b07b8b3dc38ecc4ebae97ddd87f3d8985",
+    ], ("c5", "b52b72da75d0666379e20f9b4a79c33a329a01f06a2fb7865c9062a28c1de860ba432edfd86b4cb1cb8a75b46076e3b1"));
+}
+
+#[test]
+fn test_sha512_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA512>([
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c33596fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+        "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973ebde0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b",
+    ], ("21", "3831a6a6155e509dee59a7f451eb35324d8f8f2df6e3708894740f98fdee23889f4de5adb0c5010dfb555cda77c8ab5dc902094c52de3278f35a75ebc25f093a"));
+}
+
+#[test]
+fn test_sha512_256_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA512_256>([
+        "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a",
+        "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23",
+        "bde8e1f9f19bb9fd3406c90ec6bc47bd36d8ada9f11880dbc8a22a7078b6a461",
+        "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a",
+        "9a59a052930187a97038cae692f30708aa6491923ef5194394dc68d56c74fb21",
+    ], ("fa", "c4ef36923c64e51e875720e550298a5ab8a3f2f875b1e1a4c9b95babf7344fef"));
+}
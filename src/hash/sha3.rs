@@ -0,0 +1,263 @@
+/*
+* SHA-3 family as defined by FIPS 202
+*
+* Carachteristics:
+* - sponge construction over the Keccak-f[1600] permutation (24 rounds on 25 64 bit lanes)
+* - the capacity (1600 - rate) is twice the security level
+* - SHA3-224/256/384/512: fixed output length, suffix 01
+* - SHAKE128/256: extendable output functions (XOF), suffix 1111
+*/
+
+use crate::hash::{ExtendableOutput, Hash};
+use crate::utils::BlockBuffer;
+
+// iota step round constants
+const RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// rho step rotation offsets, following the pi step lane order
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+
+// pi step lane order, lane (x, y) is at index x + 5y
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+// domain separation suffixes, already followed by the first bit of the pad10*1 padding
+const SHA3_SUFFIX: u8 = 0x06;
+const SHAKE_SUFFIX: u8 = 0x1F;
+
+// Keccak-f[1600] permutation
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in RC {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, cx) in c.iter_mut().enumerate() {
+            *cx = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut last = a[1];
+        for (&offset, &lane) in RHO.iter().zip(PI.iter()) {
+            let tmp = a[lane];
+            a[lane] = last.rotate_left(offset);
+            last = tmp;
+        }
+
+        // chi
+        for y in 0..5 {
+            let row = [a[5 * y], a[5 * y + 1], a[5 * y + 2], a[5 * y + 3], a[5 * y + 4]];
+            for x in 0..5 {
+                a[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+// sponge state shared by every SHA-3 variant
+#[derive(Clone)]
+struct Sponge {
+    state: [u64; 25],
+    // bytes absorbed (or squeezed) per permutation
+    rate: usize,
+    // bytes not yet absorbed (always less than rate)
+    buffer: BlockBuffer,
+}
+
+impl Sponge {
+    fn new(rate: usize) -> Self {
+        Self { state: [0; 25], rate, buffer: BlockBuffer::new(rate) }
+    }
+
+    // xors a full rate sized block into the state and permutes it
+    fn absorb_block(&mut self, block: &[u8]) {
+        for (lane, bytes) in self.state.iter_mut().zip(block.chunks_exact(8)) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(bytes);
+            *lane ^= u64::from_le_bytes(word);
+        }
+        keccak_f(&mut self.state);
+    }
+
+    fn absorb(&mut self, data: &[u8]) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.update(data, |block| self.absorb_block(block));
+        self.buffer = buffer;
+    }
+
+    // pads with suffix || pad10*1 and squeezes len bytes
+    fn squeeze(mut self, suffix: u8, len: usize) -> Vec<u8> {
+        let mut block = self.buffer.pending().to_vec();
+        block.push(suffix);
+        block.resize(self.rate, 0);
+        block[self.rate - 1] |= 0x80;
+        self.absorb_block(&block);
+
+        let mut out = Vec::with_capacity(len + self.rate);
+        loop {
+            out.extend(self.state.iter().flat_map(|lane| lane.to_le_bytes()).take(self.rate));
+            if out.len() >= len {
+                break;
+            }
+            keccak_f(&mut self.state);
+        }
+        out.truncate(len);
+        out
+    }
+}
+
+// defines a SHA-3 variant with the given capacity (in bytes) and default output size
+macro_rules! sha3_variant {
+    ($name:ident, $capacity:expr, $output_size:expr, $suffix:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Sponge);
+
+        impl Hash for $name {
+            const OUTPUT_SIZE: usize = $output_size;
+            const BLOCK_SIZE: usize = 200 - $capacity;
+
+            fn new() -> Self {
+                Self(Sponge::new(Self::BLOCK_SIZE))
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                self.0.absorb(data);
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                self.0.squeeze($suffix, Self::OUTPUT_SIZE)
+            }
+        }
+    };
+}
+
+sha3_variant!(SHA3_224, 56, 28, SHA3_SUFFIX);
+sha3_variant!(SHA3_256, 64, 32, SHA3_SUFFIX);
+sha3_variant!(SHA3_384, 96, 48, SHA3_SUFFIX);
+sha3_variant!(SHA3_512, 128, 64, SHA3_SUFFIX);
+
+// as Hash the XOFs output twice their security level (SHAKE128 -> 32 bytes)
+sha3_variant!(SHAKE128, 32, 32, SHAKE_SUFFIX);
+sha3_variant!(SHAKE256, 64, 64, SHAKE_SUFFIX);
+
+impl ExtendableOutput for SHAKE128 {
+    fn finalize_xof(self, len: usize) -> Vec<u8> {
+        self.0.squeeze(SHAKE_SUFFIX, len)
+    }
+}
+
+impl ExtendableOutput for SHAKE256 {
+    fn finalize_xof(self, len: usize) -> Vec<u8> {
+        self.0.squeeze(SHAKE_SUFFIX, len)
+    }
+}
+
+#[test]
+fn test_sha3_224_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA3_224>([
+        "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+        "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+        "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33",
+        "543e6868e1666c1a643630df77367ae5a62a85070a51c14cbf665cbc",
+        "d69335b93325192e516a912e6d19a15cb51c6ed5c15243e7a7fd653c",
+    ], ("01", "488286d9d32716e5881ea1ee51f36d3660d70f0db03b3f612ce9eda4"));
+}
+
+#[test]
+fn test_sha3_256_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA3_256>([
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+        "916f6061fe879741ca6469b43971dfdb28b1a32dc36cb3254e812be27aad1d18",
+        "5c8875ae474a3634ba4fd55ec85bffd661f32aca75c6d699d0cdcb6c115891c1",
+    ], ("e9", "f0d04dd1e6cfc29a4460d521796852f25d9ef8d28b44ee91ff5b759d72c1e6d6"));
+}
+
+#[test]
+fn test_sha3_384_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA3_384>([
+        "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+        "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+        "79407d3b5916b59c3e30b09822974791c313fb9ecc849e406f23592d04f625dc8c709b98b43b3852b337216179aa7fc7",
+        "eee9e24d78c1855337983451df97c8ad9eedf256c6334f8e948d252d5e0e76847aa0774ddb90a842190d2c558b4b8340",
+    ], ("80", "7541384852e10ff10d5fb6a7213a4a6c15ccc86d8bc1068ac04f69277142944f4ee50d91fdc56553db06b2f5039c8ab7"));
+}
+
+#[test]
+fn test_sha3_512_known_vectors() {
+    use crate::hash::check_known_vectors;
+
+    check_known_vectors::<SHA3_512>([
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+        "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+        "afebb2ef542e6579c50cad06d2e578f9f8dd6881d7dc824d26360feebf18a4fa73e3261122948efcfd492e74e82e2189ed0fb440d187f382270cb455f21dd185",
+        "3c3a876da14034ab60627c077bb98f7e120a2a5370212dffb3385a18d4f38859ed311d0a9d5141ce9cc5c66ee689b266a8aa18ace8282a0e0db596c90b0a7b87",
+    ], ("e5", "150240baf95fb36f8ccb87a19a41767e7aed95125075a2b2dbba6e565e1ce8575f2b042b62e29a04e9440314a821c6224182964d8b557b16a492b3806f4c39c1"));
+}
+
+#[test]
+// CAVP SHAKE ShortMsg (Len = 128) entries
+fn test_shake_known_vectors() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let mut shake128 = SHAKE128::new();
+    shake128.update(&hex_decode("84e950051876050dc851fbd99e6247b8").unwrap());
+    assert_eq!(hex_encode(&shake128.finalize_xof(16)), "8599bd89f63a848c49ca593ec37a12c6");
+
+    let mut shake256 = SHAKE256::new();
+    shake256.update(&hex_decode("f9da78c89084705c4ed0aa0cc2f5f0ee").unwrap());
+    assert_eq!(
+        hex_encode(&shake256.finalize_xof(32)),
+        "eaf2849059c4b1d184620446946a388f57dd46f593bcb753ea76705aad7b318a"
+    );
+
+    // default output length when used as Hash
+    assert_eq!(hex_encode(&SHAKE128::digest(b"")), "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26");
+    assert_eq!(
+        hex_encode(&SHAKE256::digest(b"abc")),
+        "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+    );
+}
+
+#[test]
+fn test_shake_long_output() {
+    use crate::utils::hex_encode;
+
+    // squeezing more than a rate worth of bytes (168 for SHAKE128)
+    let message: Vec<u8> = (0..200).collect();
+    let mut shake = SHAKE128::new();
+    shake.update(&message);
+    let out = shake.clone().finalize_xof(300);
+    assert_eq!(
+        hex_encode(&out[..32]),
+        "0c4234ca1e31801ae606f8b8d8e0665c66f42a21d601c2681858a92c79ad5d69"
+    );
+    assert_eq!(
+        hex_encode(&out[268..]),
+        "9b1f345feebde0f271a418c12e126fbe086095b9433e06a84f609a0c91793cc7"
+    );
+    // shorter outputs are prefixes of longer ones
+    assert_eq!(shake.finalize_xof(200), out[..200]);
+}
@@ -1,5 +1,49 @@
+// the file/directory names mirror the type they hold (es: des/des.rs -> DES)
+// and the cipher names are the usual uppercase acronyms
+#![allow(clippy::module_inception, clippy::upper_case_acronyms)]
+
 use std::ops::BitXor;
 
+pub mod aead;
+pub mod attacks;
+pub mod ciphers;
+pub mod error;
+pub mod hash;
+pub mod mac;
+pub mod operation_modes;
+pub mod padding;
+pub(crate) mod utils;
+
+pub use error::{Error, Result};
+
+// byte helpers handy outside the crate too (test vectors, tag comparison)
+pub use utils::{ct_eq, hex_decode, hex_encode};
+
+// shorter alias for the operation modes (es: cryptography_playground::modes::CBC)
+pub use operation_modes as modes;
+
+// re-export the most used types at the crate root
+pub use ciphers::{AES, DES, TripleDES, BitslicedDES, BitslicedKeySearch, MiniFeistel64, RSA, ElGamal};
+pub use ciphers::{ChaCha20, XChaCha20, Salsa20, XSalsa20};
+pub use operation_modes::{ECB, CBC, CTR, CFB, OFB, PCBC, Parallel};
+
+// everything needed to encrypt something with a single glob import
+// use cryptography_playground::prelude::*;
+pub mod prelude {
+    pub use crate::{BlockType, BlockCipher, CipherOperationMode, StreamCipher, Error};
+    pub use crate::ciphers::{AES, DES, TripleDES, BitslicedDES, BitslicedKeySearch, MiniFeistel64, RSA, ElGamal};
+    pub use crate::ciphers::{ChaCha20, XChaCha20, Salsa20, XSalsa20};
+    pub use crate::operation_modes::{ECB, CBC, CTR, CFB, OFB, PCBC, Parallel};
+    pub use crate::operation_modes::{CipherReader, CipherWriter, Decryptor, Encryptor, StreamingMode, Transform};
+    pub use crate::ciphers::rsa::{OAEP, PKCS1v15Encrypt, PKCS1v15Sign, PSS};
+    pub use crate::hash::{ExtendableOutput, Hash, MerkleDamgardHash};
+    pub use crate::hash::{MD5, SHA1, SHA224, SHA256, SHA384, SHA512, SHA512_256};
+    pub use crate::hash::{SHA3_224, SHA3_256, SHA3_384, SHA3_512, SHAKE128, SHAKE256};
+    pub use crate::aead::{AEAD, CCM, ChaCha20Poly1305, EAX, GCM};
+    pub use crate::mac::{Mac, CBCMAC, CMAC, HMAC, Poly1305};
+    pub use crate::padding::{Padding, Padded, PKCS7, AnsiX923, ISO10126, ISO7816, ZeroPadding};
+}
+
 // general trait for a Block
 // that abstracts away operations on u16,u32,u64,u128
 pub trait BlockType: Copy + Sized + BitXor<Output = Self> {
@@ -7,7 +51,8 @@ pub trait BlockType: Copy + Sized + BitXor<Output = Self> {
     const SIZE: usize;
 
     // converts a bytes array into this block
-    fn from_bytes(bytes: &[u8]) -> Self;
+    // fails if bytes isn't exactly SIZE bytes long
+    fn from_bytes(bytes: &[u8]) -> Result<Self>;
 
     // converts this block into an array of bytes
     fn to_bytes(&self) -> Vec<u8>;
@@ -16,11 +61,26 @@ pub trait BlockType: Copy + Sized + BitXor<Output = Self> {
 impl BlockType for u64 {
     const SIZE: usize = 8;
 
-    fn from_bytes(bytes: &[u8]) -> Self {
-        assert!(bytes.len() == 8, "Not an u64");
-        let mut tmp = [0u8; 8];
-        tmp.copy_from_slice(bytes);
-        u64::from_be_bytes(tmp)
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let tmp: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| Error::InvalidBlockLength { expected: 8, actual: bytes.len() })?;
+        Ok(u64::from_be_bytes(tmp))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+}
+
+impl BlockType for u128 {
+    const SIZE: usize = 16;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let tmp: [u8; 16] = bytes
+            .try_into()
+            .map_err(|_| Error::InvalidBlockLength { expected: 16, actual: bytes.len() })?;
+        Ok(u128::from_be_bytes(tmp))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
@@ -32,20 +92,49 @@ impl BlockType for u64 {
 pub trait BlockCipher {
     // implementations can define the size (es: DES u64)
     type Block: BlockType;
-    
+
     // takes a plain block and outputs a cipher block
     fn encrypt_block(&self, block: Self::Block) -> Self::Block;
 
     // takes a cipher block and outputs a plain block
     fn decrypt_block(&self, block: Self::Block) -> Self::Block;
+
+    // encrypts many blocks in place, one at a time by default
+    // ciphers that process several blocks at once (es: BitslicedDES) override it
+    fn encrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        for block in blocks.iter_mut() {
+            *block = self.encrypt_block(*block);
+        }
+    }
+
+    // decrypts many blocks in place, one at a time by default
+    fn decrypt_blocks(&self, blocks: &mut [Self::Block]) {
+        for block in blocks.iter_mut() {
+            *block = self.decrypt_block(*block);
+        }
+    }
 }
 
 // general trait for a Cipher Operation Mode (ECB, CBC, ...)
 // that wraps a BlockCipher
 pub trait CipherOperationMode<C: BlockCipher> {
     // encrypt some plaintext bytes using the provided cipher
-    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Vec<u8>;
-    
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>>;
+
     // decrypt some ciphertext bytes using the provided cipher
-    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Vec<u8>;
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+// general trait for a seekable StreamCipher (ChaCha20, ...)
+// the keystream is XORed into the data, so encryption and decryption are the same operation
+pub trait StreamCipher {
+    // XORs the keystream into data starting from the current position, then moves past it
+    // fails if the keystream would run out (es: a 32 bit block counter wrapping around)
+    fn apply_keystream(&mut self, data: &mut [u8]) -> Result<()>;
+
+    // moves to the given byte offset of the keystream
+    fn seek(&mut self, position: u64) -> Result<()>;
+
+    // current byte offset in the keystream
+    fn position(&self) -> u64;
 }
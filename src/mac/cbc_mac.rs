@@ -0,0 +1,93 @@
+/*
+* CBC-MAC (FIPS 113 / ISO/IEC 9797-1 MAC algorithm 1)
+*
+*   T = C_n   where C_i = E(C_i-1 XOR M_i), C_0 = 0
+*
+* the last incomplete block (or an empty message) is completed with zeros
+*
+* NOTE: only secure for messages of a fixed length! Given T1 = MAC(M1) anyone can
+* compute the MAC of M1 || (M2_1 XOR T1) || M2_2 ... which is MAC(M2), because the
+* chaining value after M1 is exactly T1 (see attacks::cbc_mac). Use CMAC instead.
+*/
+
+use crate::{BlockType, BlockCipher};
+use crate::mac::{Mac, load_block};
+use crate::utils::BlockBuffer;
+
+pub struct CBCMAC<'a, C: BlockCipher> {
+    cipher: &'a C,
+    // current chaining value
+    state: C::Block,
+    // bytes not yet processed (always less than a block)
+    buffer: BlockBuffer,
+    // whether at least one block has been processed
+    processed: bool,
+}
+
+impl<'a, C: BlockCipher> CBCMAC<'a, C> {
+    pub fn new(cipher: &'a C) -> Self {
+        Self {
+            cipher,
+            state: load_block(&vec![0u8; C::Block::SIZE]),
+            buffer: BlockBuffer::new(C::Block::SIZE),
+            processed: false,
+        }
+    }
+
+    // one shot CBC-MAC of data
+    pub fn mac(cipher: &'a C, data: &[u8]) -> Vec<u8> {
+        let mut cbc_mac = Self::new(cipher);
+        cbc_mac.update(data);
+        cbc_mac.finalize()
+    }
+
+    fn process_block(&mut self, block: &[u8]) {
+        self.state = self.cipher.encrypt_block(self.state ^ load_block(block));
+        self.processed = true;
+    }
+}
+
+impl<C: BlockCipher> Mac for CBCMAC<'_, C> {
+    fn update(&mut self, data: &[u8]) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.update(data, |block| self.process_block(block));
+        self.buffer = buffer;
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        if !self.buffer.pending().is_empty() || !self.processed {
+            let mut block = self.buffer.pending().to_vec();
+            block.resize(C::Block::SIZE, 0);
+            self.process_block(&block);
+        }
+        self.state.to_bytes()
+    }
+}
+
+#[test]
+// FIPS 113 / ANSI X9.9 example: the 32 bit MAC is the leftmost half of the last block
+fn test_cbc_mac_des_known_vector() {
+    use crate::DES;
+    use crate::utils::hex_encode;
+
+    let cipher = DES::new(0x0123456789abcdef);
+    let tag = CBCMAC::mac(&cipher, b"7654321 Now is the time for ");
+    assert_eq!(hex_encode(&tag), "f1d30f6849312ca4");
+    assert_eq!(hex_encode(&tag[..4]), "f1d30f68");
+}
+
+#[test]
+// CBC-MAC is the last block of a zero IV CBC encryption
+fn test_cbc_mac_matches_cbc() {
+    use crate::{AES, CBC, CipherOperationMode};
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let message: Vec<u8> = (0..64).collect();
+    let ciphertext = CBC::<AES> { iv: 0 }.encrypt(&cipher, &message).unwrap();
+
+    let mut cbc_mac = CBCMAC::new(&cipher);
+    for chunk in message.chunks(7) {
+        cbc_mac.update(chunk);
+    }
+    assert_eq!(cbc_mac.finalize(), ciphertext[48..]);
+}
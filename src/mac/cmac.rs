@@ -0,0 +1,167 @@
+/*
+* CMAC (OMAC1) as defined by NIST SP 800-38B
+*
+* CBC-MAC where the last block is masked with a subkey before being encrypted:
+*   L = E(0^b), K1 = L << 1 (XOR Rb if msb(L)), K2 = K1 << 1 (XOR Rb if msb(K1))
+*   complete last block: M_n XOR K1
+*   incomplete last block: (M_n || 10...0) XOR K2
+* this binds the tag to the message length, fixing CBC-MAC's extension forgery.
+* Rb = 0x1B for 64 bit blocks (DES, 3DES) and 0x87 for 128 bit blocks (AES)
+*/
+
+use crate::{BlockType, BlockCipher};
+use crate::mac::{Mac, load_block};
+
+pub struct CMAC<'a, C: BlockCipher> {
+    cipher: &'a C,
+    k1: C::Block,
+    k2: C::Block,
+    // current chaining value
+    state: C::Block,
+    // bytes not yet processed, the last block is only processed by finalize
+    // so it's between 0 and a whole block
+    buffer: Vec<u8>,
+}
+
+impl<'a, C: BlockCipher> CMAC<'a, C> {
+    pub fn new(cipher: &'a C) -> Self {
+        let zero = load_block(&vec![0u8; C::Block::SIZE]);
+        let (k1, k2) = Self::subkeys(cipher);
+        Self { cipher, k1, k2, state: zero, buffer: Vec::with_capacity(C::Block::SIZE) }
+    }
+
+    // one shot CMAC of data
+    pub fn mac(cipher: &'a C, data: &[u8]) -> Vec<u8> {
+        let mut cmac = Self::new(cipher);
+        cmac.update(data);
+        cmac.finalize()
+    }
+
+    // derives (K1, K2) from L = E(0^b)
+    pub fn subkeys(cipher: &C) -> (C::Block, C::Block) {
+        let l = cipher.encrypt_block(load_block(&vec![0u8; C::Block::SIZE])).to_bytes();
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        (load_block(&k1), load_block(&k2))
+    }
+
+    fn process_block(&mut self, block: &[u8]) {
+        self.state = self.cipher.encrypt_block(self.state ^ load_block(block));
+    }
+}
+
+impl<C: BlockCipher> Mac for CMAC<'_, C> {
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // a full buffer is processed only once we know it isn't the last block
+            if self.buffer.len() == C::Block::SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.process_block(&block);
+            }
+            let missing = (C::Block::SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+        }
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let mut last = std::mem::take(&mut self.buffer);
+        let subkey = if last.len() == C::Block::SIZE {
+            self.k1
+        } else {
+            // pad with 10...0
+            last.push(0x80);
+            last.resize(C::Block::SIZE, 0);
+            self.k2
+        };
+        let last = load_block::<C::Block>(&last) ^ subkey;
+        self.cipher.encrypt_block(self.state ^ last).to_bytes()
+    }
+}
+
+// doubling in GF(2^b): left shift by one bit, reduced by Rb
+fn dbl(block: &[u8]) -> Vec<u8> {
+    let rb = if block.len() == 8 { 0x1B } else { 0x87 };
+    let mut out: Vec<u8> = block
+        .iter()
+        .zip(block.iter().skip(1).chain(std::iter::once(&0)))
+        .map(|(b, next)| (b << 1) | (next >> 7))
+        .collect();
+    if block[0] & 0x80 != 0 {
+        let last = out.len() - 1;
+        out[last] ^= rb;
+    }
+    out
+}
+
+#[test]
+// NIST SP 800-38B D.1 (AES-128)
+fn test_cmac_aes_known_vectors() {
+    use crate::AES;
+    use crate::utils::{hex_decode, hex_encode};
+
+    let cipher = AES::new(&0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes()).unwrap();
+    let (k1, k2) = CMAC::subkeys(&cipher);
+    assert_eq!(k1, 0xfbeed618357133667c85e08f7236a8de);
+    assert_eq!(k2, 0xf7ddac306ae266ccf90bc11ee46d513b);
+
+    let message = hex_decode("
+        6bc1bee22e409f96e93d7e117393172a ae2d8a571e03ac9c9eb76fac45af8e51
+        30c81c46a35ce411e5fbc1191a0a52ef f69f2445df4f9b17ad2b417be66c3710
+    ").unwrap();
+    let vectors = [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (40, "dfa66747de9ae63030ca32611497c827"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(hex_encode(&CMAC::mac(&cipher, &message[..len])), tag, "Mlen = {}", len * 8);
+    }
+}
+
+#[test]
+// NIST SP 800-38B, appendix D.4 (three key TDEA), examples 9 to 12
+fn test_cmac_tdes_known_vectors() {
+    use crate::TripleDES;
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key = hex_decode("8aa83bf8cbda1062 0bc1bf19fbb6cd58 bc313d4a371ca8b5").unwrap();
+    let cipher = TripleDES::from_key_bundle(&key).unwrap();
+    let (k1, k2) = CMAC::subkeys(&cipher);
+    assert_eq!(k1, 0x9198e9d314e6535f);
+    assert_eq!(k2, 0x2331d3a629cca6a5);
+
+    let message = hex_decode("6bc1bee22e409f96 e93d7e117393172a ae2d8a571e03ac9c 9eb76fac45af8e51").unwrap();
+    let vectors = [
+        (0, "b7a688e122ffaf95"),
+        (8, "8e8f293136283797"),
+        (20, "743ddbe0ce2dc2ed"),
+        (32, "33e6b1092400eae5"),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(hex_encode(&CMAC::mac(&cipher, &message[..len])), tag, "Mlen = {}", len * 8);
+    }
+}
+
+#[test]
+fn test_cmac_streaming_and_verify() {
+    use crate::{AES, Error};
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let message: Vec<u8> = (0..48).collect();
+    let tag = CMAC::mac(&cipher, &message);
+
+    // split exactly on block boundaries and unevenly
+    for chunk_size in [1, 7, 16, 48] {
+        let mut cmac = CMAC::new(&cipher);
+        for chunk in message.chunks(chunk_size) {
+            cmac.update(chunk);
+        }
+        assert_eq!(cmac.verify(&tag), Ok(()));
+    }
+
+    let mut cmac = CMAC::new(&cipher);
+    cmac.update(&message[..47]);
+    assert_eq!(cmac.verify(&tag), Err(Error::InvalidTag));
+}
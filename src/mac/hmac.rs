@@ -0,0 +1,192 @@
+/*
+* HMAC as defined by RFC 2104
+*
+*   HMAC(K, m) = H((K' ^ opad) || H((K' ^ ipad) || m))
+*
+* K' is the key padded with zeros to the block size of H (hashed first if longer).
+* Unlike H(K || m) it isn't affected by length extension, the inner digest is
+* hashed again under a different key.
+*/
+
+use crate::hash::Hash;
+use crate::mac::Mac;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+pub struct HMAC<H: Hash> {
+    // inner hash, already fed with K' ^ ipad
+    inner: H,
+    // K' ^ opad, kept for the outer hash
+    outer_key: Vec<u8>,
+}
+
+impl<H: Hash> HMAC<H> {
+    pub fn new(key: &[u8]) -> Self {
+        // 1. K' = H(K) if K is longer than a block, then pad it with zeros
+        let mut padded_key = if key.len() > H::BLOCK_SIZE { H::digest(key) } else { key.to_vec() };
+        padded_key.resize(H::BLOCK_SIZE, 0);
+
+        // 2. start the inner hash with K' ^ ipad
+        let mut inner = H::new();
+        inner.update(&padded_key.iter().map(|b| b ^ IPAD).collect::<Vec<u8>>());
+        let outer_key = padded_key.iter().map(|b| b ^ OPAD).collect();
+
+        Self { inner, outer_key }
+    }
+
+    // one shot HMAC of data
+    pub fn mac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut hmac = Self::new(key);
+        hmac.update(data);
+        hmac.finalize()
+    }
+}
+
+impl<H: Hash> Mac for HMAC<H> {
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        let mut outer = H::new();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+}
+
+#[cfg(test)]
+// RFC 4231 test cases 1-7 for one hash function
+// NOTE: test case 5 truncates the output to 128 bits
+fn check_rfc4231<H: Hash>(expected: [&str; 7]) {
+    use crate::utils::hex_encode;
+
+    let cases: [(Vec<u8>, &[u8]); 7] = [
+        (vec![0x0b; 20], b"Hi There"),
+        (b"Jefe".to_vec(), b"what do ya want for nothing?"),
+        (vec![0xaa; 20], &[0xdd; 50]),
+        ((1..=25).collect(), &[0xcd; 50]),
+        (vec![0x0c; 20], b"Test With Truncation"),
+        (vec![0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+        (
+            vec![0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size data. \
+              The key needs to be hashed before being used by the HMAC algorithm.",
+        ),
+    ];
+    for (i, ((key, data), tag)) in cases.iter().zip(expected).enumerate() {
+        let computed = hex_encode(&HMAC::<H>::mac(key, data));
+        if i == 4 {
+            assert_eq!(computed[..32], tag[..32], "test case {}", i + 1);
+        } else {
+            assert_eq!(computed, tag, "test case {}", i + 1);
+        }
+    }
+}
+
+#[test]
+fn test_hmac_sha224_rfc4231() {
+    check_rfc4231::<crate::hash::SHA224>([
+        "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+        "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+        "7fb3cb3588c6c1f6ffa9694d7d6ad2649365b0c1f65d69d1ec8333ea",
+        "6c11506874013cac6a2abc1bb382627cec6a90d86efc012de7afec5a",
+        "0e2aea68a90c8d37c988bcdb9fca6fa8",
+        "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+        "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+    ]);
+}
+
+#[test]
+fn test_hmac_sha256_rfc4231() {
+    check_rfc4231::<crate::hash::SHA256>([
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+        "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+        "a3b6167473100ee06e0c796c2955552b",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+    ]);
+}
+
+#[test]
+fn test_hmac_sha384_rfc4231() {
+    check_rfc4231::<crate::hash::SHA384>([
+        "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+        "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+        "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b2a5ab39dc13814b94e3ab6e101a34f27",
+        "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e6801dd23c4a7d679ccf8a386c674cffb",
+        "3abf34c3503b2a23a46efc619baef897",
+        "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+        "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+    ]);
+}
+
+#[test]
+fn test_hmac_sha512_rfc4231() {
+    check_rfc4231::<crate::hash::SHA512>([
+        "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+        "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+        "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+        "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3dba91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+        "415fad6271580a531d4179bc891d87a6",
+        "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+    ]);
+}
+
+#[test]
+fn test_hmac_streaming_and_verify() {
+    use crate::Error;
+    use crate::hash::SHA256;
+
+    let key = b"Jefe";
+    let data = b"what do ya want for nothing?";
+    let tag = HMAC::<SHA256>::mac(key, data);
+
+    let mut hmac = HMAC::<SHA256>::new(key);
+    for chunk in data.chunks(5) {
+        hmac.update(chunk);
+    }
+    assert_eq!(hmac.verify(&tag), Ok(()));
+
+    let mut tampered = tag.clone();
+    tampered[31] ^= 0x01;
+    let mut hmac = HMAC::<SHA256>::new(key);
+    hmac.update(data);
+    assert_eq!(hmac.verify(&tampered), Err(Error::InvalidTag));
+
+    // truncated tags are rejected too
+    let mut hmac = HMAC::<SHA256>::new(key);
+    hmac.update(data);
+    assert_eq!(hmac.verify(&tag[..16]), Err(Error::InvalidTag));
+}
+
+#[test]
+// Encrypt-then-MAC over CBC: the tag covers IV and ciphertext and is checked before decrypting
+fn test_hmac_encrypt_then_mac() {
+    use crate::{AES, CBC, CipherOperationMode, Error};
+    use crate::hash::SHA256;
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let mode = CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let mac_key = [0x4d; 32];
+
+    let ciphertext = mode.encrypt(&cipher, &[0x42; 32]).unwrap();
+    let authenticated = [&mode.iv.to_be_bytes()[..], &ciphertext].concat();
+    let tag = HMAC::<SHA256>::mac(&mac_key, &authenticated);
+
+    let mut hmac = HMAC::<SHA256>::new(&mac_key);
+    hmac.update(&authenticated);
+    assert_eq!(hmac.verify(&tag), Ok(()));
+    assert_eq!(mode.decrypt(&cipher, &ciphertext).unwrap(), [0x42; 32]);
+
+    // a flipped ciphertext bit is caught before touching the plaintext
+    let mut tampered = authenticated.clone();
+    tampered[20] ^= 0x80;
+    let mut hmac = HMAC::<SHA256>::new(&mac_key);
+    hmac.update(&tampered);
+    assert_eq!(hmac.verify(&tag), Err(Error::InvalidTag));
+}
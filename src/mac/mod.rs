@@ -0,0 +1,40 @@
+pub mod cbc_mac;
+pub mod cmac;
+pub mod hmac;
+pub mod poly1305;
+
+pub use cbc_mac::CBCMAC;
+pub use cmac::CMAC;
+pub use hmac::HMAC;
+pub use poly1305::Poly1305;
+
+use crate::{BlockType, Error, Result};
+use crate::utils::ct_eq;
+
+// general trait for a Message Authentication Code with a streaming interface
+// (es: HMAC), the key is given to the constructor of each implementation
+pub trait Mac {
+    // absorbs some more data
+    fn update(&mut self, data: &[u8]);
+
+    // outputs the tag of everything absorbed so far
+    fn finalize(self) -> Vec<u8>;
+
+    // checks tag against the computed one in constant time
+    fn verify(self, tag: &[u8]) -> Result<()>
+    where
+        Self: Sized,
+    {
+        if ct_eq(&self.finalize(), tag) {
+            Ok(())
+        } else {
+            Err(Error::InvalidTag)
+        }
+    }
+}
+
+// converts a chunk that is exactly one block long (es: a full buffer) into a block
+// NOTE: from_bytes can only fail on a length mismatch, which callers rule out
+pub(crate) fn load_block<B: BlockType>(chunk: &[u8]) -> B {
+    B::from_bytes(chunk).expect("chunk must be exactly one block long")
+}
@@ -0,0 +1,197 @@
+/*
+* Poly1305 one-time authenticator as defined by RFC 8439
+*
+*   tag = ((c_1 * r^q + c_2 * r^(q-1) + ... + c_q * r) mod 2^130 - 5) + s  mod 2^128
+*
+* where the c_i are the 16 bytes message chunks with a 0x01 byte appended,
+* r is the clamped first half of the 32 bytes key and s the second half.
+* Arithmetic uses 5 limbs of 26 bits so that products fit in an u64 (poly1305-donna).
+*
+* NOTE: a key must never be used twice (ChaCha20-Poly1305 derives one per nonce)
+*/
+
+use crate::{Error, Result};
+use crate::mac::Mac;
+use crate::utils::BlockBuffer;
+
+const BLOCK_SIZE: usize = 16;
+const MASK26: u32 = 0x3ffffff;
+
+pub struct Poly1305 {
+    // clamped r in 26 bit limbs
+    r: [u32; 5],
+    // s as 4 little-endian words
+    s: [u32; 4],
+    // accumulator in 26 bit limbs
+    h: [u32; 5],
+    // bytes not yet processed (always less than a block)
+    buffer: BlockBuffer,
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        if key.len() != 32 {
+            return Err(Error::InvalidKeyLength(key.len()));
+        }
+
+        // r is clamped: top 4 bits of bytes 3, 7, 11, 15 and bottom 2 bits of 4, 8, 12 cleared
+        let r = [
+            le32(&key[0..]) & 0x3ffffff,
+            (le32(&key[3..]) >> 2) & 0x3ffff03,
+            (le32(&key[6..]) >> 4) & 0x3ffc0ff,
+            (le32(&key[9..]) >> 6) & 0x3f03fff,
+            (le32(&key[12..]) >> 8) & 0x00fffff,
+        ];
+        let s = [le32(&key[16..]), le32(&key[20..]), le32(&key[24..]), le32(&key[28..])];
+        Ok(Self { r, s, h: [0; 5], buffer: BlockBuffer::new(BLOCK_SIZE) })
+    }
+
+    // one shot Poly1305 of data
+    pub fn mac(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        let mut poly = Self::new(key)?;
+        poly.update(data);
+        Ok(poly.finalize())
+    }
+
+    // h = (h + block) * r mod 2^130 - 5
+    // hibit is the 2^128 bit appended to full blocks (partial ones are padded by the caller)
+    fn process_block(&mut self, block: &[u8], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+        let h = &mut self.h;
+        h[0] += le32(&block[0..]) & MASK26;
+        h[1] += (le32(&block[3..]) >> 2) & MASK26;
+        h[2] += (le32(&block[6..]) >> 4) & MASK26;
+        h[3] += (le32(&block[9..]) >> 6) & MASK26;
+        h[4] += (le32(&block[12..]) >> 8) | hibit;
+        let [h0, h1, h2, h3, h4] = h.map(u64::from);
+
+        // limbs above 2^130 wrap around multiplied by 5 (2^130 = 5 mod p)
+        let d = [
+            h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1,
+            h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2,
+            h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3,
+            h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4,
+            h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0,
+        ];
+
+        // partial carry propagation
+        let mut carry = 0u64;
+        for (hi, di) in h.iter_mut().zip(d) {
+            let v = di + carry;
+            *hi = (v as u32) & MASK26;
+            carry = v >> 26;
+        }
+        h[0] += (carry * 5) as u32;
+        let carry = h[0] >> 26;
+        h[0] &= MASK26;
+        h[1] += carry;
+    }
+}
+
+impl Mac for Poly1305 {
+    fn update(&mut self, data: &[u8]) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.update(data, |block| self.process_block(block, 1 << 24));
+        self.buffer = buffer;
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        // 1. last partial block: append 0x01 and zeros instead of the 2^128 bit
+        if !self.buffer.pending().is_empty() {
+            let mut block = self.buffer.pending().to_vec();
+            block.push(0x01);
+            block.resize(BLOCK_SIZE, 0);
+            self.process_block(&block, 0);
+        }
+
+        // 2. fully carry h
+        let mut h = self.h;
+        for i in 1..5 {
+            h[i] += h[i - 1] >> 26;
+            h[i - 1] &= MASK26;
+        }
+        h[0] += (h[4] >> 26) * 5;
+        h[4] &= MASK26;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK26;
+
+        // 3. g = h - p = h + 5 - 2^130, select g if it isn't negative (constant time)
+        let mut g = [0u32; 5];
+        let mut carry = 5;
+        for (gi, hi) in g.iter_mut().zip(h) {
+            let v = hi + carry;
+            *gi = v & MASK26;
+            carry = v >> 26;
+        }
+        g[4] = g[4].wrapping_add(carry << 26).wrapping_sub(1 << 26);
+        let use_g = (g[4] >> 31).wrapping_sub(1);
+        for (hi, gi) in h.iter_mut().zip(g) {
+            *hi = (*hi & !use_g) | (gi & use_g);
+        }
+
+        // 4. h mod 2^128 as 4 words, plus s
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut out = Vec::with_capacity(16);
+        let mut carry = 0u64;
+        for (w, s) in words.iter().zip(self.s) {
+            let v = *w as u64 + s as u64 + carry;
+            out.extend_from_slice(&(v as u32).to_le_bytes());
+            carry = v >> 32;
+        }
+        out
+    }
+}
+
+// little-endian word from the first 4 bytes
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[test]
+// RFC 8439 2.5.2 test vector
+fn test_poly1305_rfc8439() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    let key = hex_decode("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b").unwrap();
+    let tag = Poly1305::mac(&key, b"Cryptographic Forum Research Group").unwrap();
+    assert_eq!(hex_encode(&tag), "a8061dc1305136c6c22b8baf0c0127a9");
+
+    let mut poly = Poly1305::new(&key).unwrap();
+    for chunk in b"Cryptographic Forum Research Group".chunks(5) {
+        poly.update(chunk);
+    }
+    assert_eq!(poly.verify(&tag), Ok(()));
+}
+
+#[test]
+// RFC 8439 appendix A.3 test vectors 6-8, exercising the final reduction
+fn test_poly1305_edge_cases() {
+    use crate::utils::{hex_decode, hex_encode};
+
+    // r = 2 or r = 1 with the given s
+    let key = |r: u8, s: u8| [&[r][..], &[0; 15], &[s; 16]].concat();
+
+    // 6. h exceeds p before the reduction
+    let data = hex_decode("ffffffffffffffffffffffffffffffff").unwrap();
+    assert_eq!(hex_encode(&Poly1305::mac(&key(2, 0), &data).unwrap()), "03000000000000000000000000000000");
+
+    // 7. h + s overflows 2^128
+    let data = hex_decode("02000000000000000000000000000000").unwrap();
+    assert_eq!(hex_encode(&Poly1305::mac(&key(2, 0xff), &data).unwrap()), "03000000000000000000000000000000");
+
+    // 8. h lands between p and 2^130
+    let data = hex_decode("
+        ffffffffffffffffffffffffffffffff f0ffffffffffffffffffffffffffffff
+        11000000000000000000000000000000
+    ").unwrap();
+    assert_eq!(hex_encode(&Poly1305::mac(&key(1, 0), &data).unwrap()), "05000000000000000000000000000000");
+
+    assert_eq!(Poly1305::new(&[0; 16]).err(), Some(Error::InvalidKeyLength(16)));
+}
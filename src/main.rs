@@ -1,19 +1,18 @@
-use crate::ciphers::rsa::rsa::RSA;
+use num_bigint::BigUint;
 
-mod ciphers; 
-mod operation_modes;
-mod utils;
+use cryptography_playground::prelude::*;
 
-
-fn main() {
-    let message = 2u64;
+fn main() -> Result<(), Error> {
+    let message = BigUint::from(2u32);
     
-    let cipher = RSA::new(14, 5, 11); 
+    let cipher = RSA::gen_keypair(2048)?;
 
-    let ciphertext = cipher.encrypt_message(message);
+    let ciphertext = cipher.encrypt_message(&message)?;
     println!("CIPHERTEXT: {}", ciphertext);
 
-    let decrypted = cipher.decrypt_message(ciphertext);
+    let decrypted = cipher.decrypt_message(&ciphertext)?;
     println!("DECRYPTED: {}", decrypted);
     assert_eq!(decrypted, message);
+
+    Ok(())
 }
@@ -1,22 +1,21 @@
-use cryptography_playground::{BlockType, BlockCipher, CipherOperationMode};
+use crate::{BlockType, BlockCipher, CipherOperationMode, Result};
+use crate::error::check_block_aligned;
+use crate::operation_modes::streaming::StreamingMode;
 
 pub struct CBC<C: BlockCipher> {
     pub iv: C::Block
 }
 
 impl<C: BlockCipher> CipherOperationMode<C> for CBC<C> {
-    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Vec<u8> {
-        // assert that plaintext is a multiple of the cipher's block size
-        assert!(
-            plaintext.len() % C::Block::SIZE == 0,
-            "Plaintext must be a multiple of block size"
-        );
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that plaintext is a multiple of the cipher's block size
+        check_block_aligned(plaintext, C::Block::SIZE)?;
         
         let mut out = Vec::new();
         let mut prev = self.iv;
-        for (_, chunk) in plaintext.chunks(C::Block::SIZE).enumerate() {
+        for chunk in plaintext.chunks(C::Block::SIZE) {
             // reconstruct i-th block from the i-th chunk
-            let plain_block = C::Block::from_bytes(chunk);
+            let plain_block = C::Block::from_bytes(chunk)?;
             // run the encryption function on plain_block XOR prev
             let cipher_block = cipher.encrypt_block(plain_block ^ prev); 
             // update prev
@@ -25,21 +24,18 @@ impl<C: BlockCipher> CipherOperationMode<C> for CBC<C> {
             out.append(&mut cipher_block.to_bytes());
         }
 
-        out
+        Ok(out)
     } 
 
-    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Vec<u8> {
-        // assert that ciphertext is a multiple of the cipher's block size
-        assert!(
-            ciphertext.len() % C::Block::SIZE == 0,
-            "Plaintext must be a multiple of block size"
-        );
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that ciphertext is a multiple of the cipher's block size
+        check_block_aligned(ciphertext, C::Block::SIZE)?;
 
         let mut out = Vec::new();
         let mut prev = self.iv;
-        for (_, chunk) in ciphertext.chunks(C::Block::SIZE).enumerate() {
+        for chunk in ciphertext.chunks(C::Block::SIZE) {
             // reconstruct i-th block from i-th chunk
-            let cipher_block = C::Block::from_bytes(chunk);
+            let cipher_block = C::Block::from_bytes(chunk)?;
             // run decryption function on it
             let plain_block = cipher.decrypt_block(cipher_block) ^ prev;
             // update prev
@@ -48,6 +44,18 @@ impl<C: BlockCipher> CipherOperationMode<C> for CBC<C> {
             out.append(&mut plain_block.to_bytes());
         }
 
-        out
+        Ok(out)
+    }
+}
+
+// the IV of the next piece is the last ciphertext block
+impl<C: BlockCipher> StreamingMode<C> for CBC<C> {
+    fn advance(&self, input: &[u8], output: &[u8], decrypt: bool) -> Result<Self> {
+        let ciphertext = if decrypt { input } else { output };
+        let iv = match ciphertext.len() {
+            0 => self.iv,
+            len => C::Block::from_bytes(&ciphertext[len - C::Block::SIZE..])?,
+        };
+        Ok(Self { iv })
     }
 }
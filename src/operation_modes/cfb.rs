@@ -0,0 +1,145 @@
+/*
+* Cipher Feedback mode (NIST SP 800-38A)
+*
+* the previous ciphertext segment is fed back into a shift register
+* whose encryption is used as keystream:
+*   O_i = E(I_i)
+*   C_i = P_i XOR MSB_s(O_i)
+*   I_i+1 = LSB_b-s(I_i) || C_i
+* with I_1 = IV and s the segment size
+*
+* - segment_size = block size: full-block CFB (es: CFB-64 for DES, CFB-128 for AES)
+* - segment_size = 1: CFB-8, one cipher invocation for every byte
+*
+* the input can have any length, the last segment is truncated
+*/
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Error, Result};
+use crate::operation_modes::streaming::StreamingMode;
+
+pub struct CFB<C: BlockCipher> {
+    pub iv: C::Block,
+    // number of bytes processed for every cipher invocation, in [1, block size]
+    pub segment_size: usize,
+}
+
+impl<C: BlockCipher> CFB<C> {
+    // full-block CFB
+    pub fn new(iv: C::Block) -> Self {
+        Self { iv, segment_size: C::Block::SIZE }
+    }
+
+    // CFB-8 (8 bit segments)
+    pub fn cfb8(iv: C::Block) -> Self {
+        Self { iv, segment_size: 1 }
+    }
+
+    // runs CFB over input, decrypt only changes which segment is fed back
+    fn process(&self, cipher: &C, input: &[u8], decrypt: bool) -> Result<Vec<u8>> {
+        if self.segment_size == 0 || self.segment_size > C::Block::SIZE {
+            return Err(Error::InvalidParameter("segment size must be in [1, block size]"));
+        }
+
+        let mut out = Vec::with_capacity(input.len());
+        let mut register = self.iv.to_bytes();
+        for segment in input.chunks(self.segment_size) {
+            // encrypt the shift register and use its MSBs as keystream
+            let keystream = cipher.encrypt_block(C::Block::from_bytes(&register)?).to_bytes();
+            let processed: Vec<u8> = segment.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+
+            // shift the ciphertext segment into the register
+            let cipher_segment = if decrypt { segment } else { &processed[..] };
+            register.drain(..cipher_segment.len());
+            register.extend_from_slice(cipher_segment);
+
+            out.extend(processed);
+        }
+
+        Ok(out)
+    }
+}
+
+impl<C: BlockCipher> CipherOperationMode<C> for CFB<C> {
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.process(cipher, plaintext, false)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.process(cipher, ciphertext, true)
+    }
+}
+
+// pieces are whole segments, the shift register holds the last
+// block size bytes of IV || ciphertext
+impl<C: BlockCipher> StreamingMode<C> for CFB<C> {
+    fn unit_size(&self) -> usize {
+        self.segment_size
+    }
+
+    fn advance(&self, input: &[u8], output: &[u8], decrypt: bool) -> Result<Self> {
+        let ciphertext = if decrypt { input } else { output };
+        let mut register = self.iv.to_bytes();
+        register.extend_from_slice(ciphertext);
+        let iv = C::Block::from_bytes(&register[register.len() - C::Block::SIZE..])?;
+        Ok(Self { iv, segment_size: self.segment_size })
+    }
+}
+
+#[test]
+// NIST SP 800-38A F.3.13 (CFB128-AES128) and F.3.7 (CFB8-AES128)
+fn test_cfb_aes_known_vectors() {
+    use crate::AES;
+
+    let cipher = AES::new(&0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes()).unwrap();
+    let iv = 0x000102030405060708090a0b0c0d0e0f;
+    let plaintext = [
+        0x6bc1bee22e409f96e93d7e117393172au128.to_be_bytes(),
+        0xae2d8a571e03ac9c9eb76fac45af8e51u128.to_be_bytes(),
+    ].concat();
+
+    let cfb = CFB::<AES>::new(iv);
+    let expected = [
+        0x3b3fd92eb72dad20333449f8e83cfb4au128.to_be_bytes(),
+        0xc8a64537a0b3a93fcde3cdad9f1ce58bu128.to_be_bytes(),
+    ].concat();
+    let ciphertext = cfb.encrypt(&cipher, &plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "CFB128-AES128 failed encryption");
+    assert_eq!(cfb.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "CFB128-AES128 failed decryption");
+
+    let cfb8 = CFB::<AES>::cfb8(iv);
+    let expected: [u8; 18] = [
+        0x3b, 0x79, 0x42, 0x4c, 0x9c, 0x0d, 0xd4, 0x36, 0xba,
+        0xce, 0x9e, 0x0e, 0xd4, 0x58, 0x6a, 0x4f, 0x32, 0xb9,
+    ];
+    let ciphertext = cfb8.encrypt(&cipher, &plaintext[..18]).unwrap();
+    assert_eq!(ciphertext, expected, "CFB8-AES128 failed encryption");
+    assert_eq!(cfb8.decrypt(&cipher, &ciphertext).unwrap(), plaintext[..18], "CFB8-AES128 failed decryption");
+}
+
+#[test]
+// FIPS 81 Appendix B (64 bit CFB and 8 bit CFB)
+fn test_cfb_des_known_vectors() {
+    use crate::DES;
+
+    let cipher = DES::new(0x0123456789abcdef);
+    let iv = 0x1234567890abcdef;
+    let plaintext = b"Now is the time for all ";
+
+    let expected: Vec<u8> = [0xf3096249c7f46e51u64, 0xa69e839b1a92f784, 0x03467133898ea622]
+        .iter()
+        .flat_map(|b| b.to_be_bytes())
+        .collect();
+    let cfb = CFB::<DES>::new(iv);
+    let ciphertext = cfb.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "CFB64-DES failed encryption");
+    assert_eq!(cfb.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "CFB64-DES failed decryption");
+
+    let expected: Vec<u8> = [0xf31fda07011462eeu64, 0x187f43d80a7cd9b5, 0xb0d290da6e5b9a87]
+        .iter()
+        .flat_map(|b| b.to_be_bytes())
+        .collect();
+    let cfb8 = CFB::<DES>::cfb8(iv);
+    let ciphertext = cfb8.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "CFB8-DES failed encryption");
+    assert_eq!(cfb8.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "CFB8-DES failed decryption");
+}
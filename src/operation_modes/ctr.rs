@@ -0,0 +1,184 @@
+/*
+* Counter mode (NIST SP 800-38A)
+*
+* turns a BlockCipher into a stream cipher:
+*   O_i = E(counter block i)
+*   C_i = P_i XOR O_i
+* encryption and decryption are the same operation
+* and the input can have any length (the last keystream block is truncated)
+*
+* the counter block is split into a nonce (most significant bits)
+* and a big-endian counter (the counter_bits least significant bits),
+* es: 96 bit nonce + 32 bit counter for 128 bit blocks
+*/
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Error, Result};
+use crate::operation_modes::streaming::StreamingMode;
+
+pub struct CTR<C: BlockCipher> {
+    // first counter block (nonce || initial counter value)
+    pub counter_block: C::Block,
+    // how many least significant bits of the counter block are incremented
+    pub counter_bits: u32,
+}
+
+impl<C: BlockCipher> CTR<C> {
+    // builds the first counter block as nonce || 0
+    // es: CTR::new(&[0u8; 12], 32) for a 96 bit nonce and a 32 bit counter
+    pub fn new(nonce: &[u8], counter_bits: u32) -> Result<Self> {
+        if nonce.len() * 8 + counter_bits as usize != C::Block::SIZE * 8 {
+            return Err(Error::InvalidParameter("nonce and counter must fill exactly one block"));
+        }
+
+        let mut bytes = nonce.to_vec();
+        bytes.resize(C::Block::SIZE, 0);
+        let ctr = Self { counter_block: C::Block::from_bytes(&bytes)?, counter_bits };
+
+        // a full block nonce leaves no counter at all
+        ctr.check_counter_bits()?;
+        Ok(ctr)
+    }
+
+    // the same CTR, starting blocks counter values later
+    // (used to split the keystream among threads)
+    pub(crate) fn skip(&self, blocks: u128) -> Result<Self> {
+        self.check_counter_bits()?;
+
+        let mask = u128::MAX >> (128 - self.counter_bits);
+        let initial = block_to_u128(self.counter_block);
+        let counter = initial & mask;
+        if blocks > mask - counter {
+            return Err(Error::CounterOverflow);
+        }
+
+        let counter_block = u128_to_block::<C::Block>((initial & !mask) | (counter + blocks))?;
+        Ok(Self { counter_block, counter_bits: self.counter_bits })
+    }
+
+    pub(crate) fn check_counter_bits(&self) -> Result<()> {
+        if self.counter_bits == 0 || self.counter_bits as usize > C::Block::SIZE * 8 {
+            return Err(Error::InvalidParameter("counter size must be in [1, block size] bits"));
+        }
+        Ok(())
+    }
+
+    // applies the keystream to input, used both to encrypt and decrypt
+    fn apply_keystream(&self, cipher: &C, input: &[u8]) -> Result<Vec<u8>> {
+        self.check_counter_bits()?;
+
+        // 1. split the counter block into nonce and counter
+        // NOTE: blocks are at most 128 bit long so an u128 can hold any of them
+        let mask = u128::MAX >> (128 - self.counter_bits);
+        let initial = block_to_u128(self.counter_block);
+        let nonce = initial & !mask;
+        let counter = initial & mask;
+
+        // 2. make sure the counter won't wrap around (reusing a keystream block)
+        let blocks = input.len().div_ceil(C::Block::SIZE) as u128;
+        if blocks > 0 && blocks - 1 > mask - counter {
+            return Err(Error::CounterOverflow);
+        }
+
+        // 3. encrypt all the counter blocks in a single batch
+        let mut keystream = (0..blocks)
+            .map(|i| u128_to_block::<C::Block>(nonce | (counter + i)))
+            .collect::<Result<Vec<_>>>()?;
+        cipher.encrypt_blocks(&mut keystream);
+
+        // 4. XOR every chunk with the encryption of its counter block
+        let mut out = Vec::with_capacity(input.len());
+        for (chunk, block) in input.chunks(C::Block::SIZE).zip(keystream) {
+            // zip() truncates the keystream for the last partial chunk
+            out.extend(chunk.iter().zip(block.to_bytes()).map(|(b, k)| b ^ k));
+        }
+
+        Ok(out)
+    }
+}
+
+impl<C: BlockCipher> CipherOperationMode<C> for CTR<C> {
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, plaintext)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, ciphertext)
+    }
+}
+
+// interprets a block as a big-endian number
+fn block_to_u128<B: BlockType>(block: B) -> u128 {
+    block.to_bytes().iter().fold(0u128, |acc, &b| (acc << 8) | b as u128)
+}
+
+// converts a big-endian number back into a block
+fn u128_to_block<B: BlockType>(value: u128) -> Result<B> {
+    B::from_bytes(&value.to_be_bytes()[16 - B::SIZE..])
+}
+
+// the next piece starts after the counter values used so far
+impl<C: BlockCipher> StreamingMode<C> for CTR<C> {
+    fn advance(&self, input: &[u8], _output: &[u8], _decrypt: bool) -> Result<Self> {
+        self.skip(input.len().div_ceil(C::Block::SIZE) as u128)
+    }
+}
+
+#[test]
+// NIST SP 800-38A F.5.1 and F.5.2 (CTR-AES128)
+fn test_ctr_aes_known_vector() {
+    use crate::AES;
+
+    let cipher = AES::new(&0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes()).unwrap();
+    let ctr = CTR::<AES> { counter_block: 0xf0f1f2f3f4f5f6f7f8f9fafbfcfdfeff, counter_bits: 32 };
+
+    let plaintext = [
+        0x6bc1bee22e409f96e93d7e117393172au128.to_be_bytes(),
+        0xae2d8a571e03ac9c9eb76fac45af8e51u128.to_be_bytes(),
+    ].concat();
+    let expected = [
+        0x874d6191b620e3261bef6864990db6ceu128.to_be_bytes(),
+        0x9806f66b7970fdff8617187bb9fffdffu128.to_be_bytes(),
+    ].concat();
+
+    let ciphertext = ctr.encrypt(&cipher, &plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "CTR-AES128 failed encryption");
+    assert_eq!(ctr.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "CTR-AES128 failed decryption");
+
+    // any length works and is a prefix of the full keystream
+    assert_eq!(ctr.encrypt(&cipher, &plaintext[..21]).unwrap(), expected[..21]);
+}
+
+#[test]
+fn test_ctr_des_roundtrip() {
+    use crate::DES;
+
+    let cipher = DES::new(0x133457799BBCDFF1);
+    let ctr = CTR::<DES>::new(&[0xAB, 0xCD, 0xEF, 0x01], 32).unwrap();
+    let plaintext = b"counter mode needs no padding";
+
+    let ciphertext = ctr.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(ciphertext.len(), plaintext.len());
+    assert_eq!(ctr.decrypt(&cipher, &ciphertext).unwrap(), plaintext);
+}
+
+#[test]
+fn test_ctr_counter_overflow() {
+    use crate::DES;
+
+    let cipher = DES::new(0x133457799BBCDFF1);
+    // only 2 counter values left (0xFE, 0xFF) but 3 blocks to encrypt
+    let ctr = CTR::<DES> { counter_block: 0x00000000000000FE, counter_bits: 8 };
+    assert_eq!(ctr.encrypt(&cipher, &[0u8; 17]), Err(Error::CounterOverflow));
+    assert!(ctr.encrypt(&cipher, &[0u8; 16]).is_ok());
+}
+
+#[test]
+fn test_ctr_new_rejects_bad_layouts() {
+    use crate::{AES, DES};
+
+    // nonce and counter must fill the block, and the counter can't be empty
+    assert!(matches!(CTR::<AES>::new(&[0u8; 12], 16), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CTR::<AES>::new(&[0u8; 16], 0), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CTR::<DES>::new(&[0u8; 8], 0), Err(Error::InvalidParameter(_))));
+    assert!(CTR::<AES>::new(&[], 128).is_ok());
+}
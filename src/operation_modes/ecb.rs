@@ -1,45 +1,46 @@
-use cryptography_playground::{BlockType, BlockCipher, CipherOperationMode};
+use crate::{BlockType, BlockCipher, CipherOperationMode, Result};
+use crate::error::check_block_aligned;
+use crate::operation_modes::streaming::StreamingMode;
 
 pub struct ECB;
 
 impl<C:BlockCipher> CipherOperationMode<C> for ECB {
-    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Vec<u8> {
-        // assert that plaintext is a multiple of the cipher's block size
-        assert!(
-            plaintext.len() % C::Block::SIZE == 0, 
-            "Plaintext must be a multiple of block size"
-        );
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that plaintext is a multiple of the cipher's block size
+        check_block_aligned(plaintext, C::Block::SIZE)?;
         
         // .chunks() -> returns an iterator over plaintext splitted in [u8; C::BLOCK::SIZE]
-        // .map() -> applies the encryption function to each block
-        // .flatten() -> returns an iterator that merges toghether all the Vec<8> returned by the map()
-        // .collect() -> turns such iterator into a collection of type Vec<u8>
-        plaintext
+        // .map() -> converts each chunk to the block type (es: u64, u128)
+        // .collect() -> turns such iterator into a Result<Vec<C::Block>>, stopping at the first error
+        let mut blocks = plaintext
             .chunks(C::Block::SIZE)
-            .map(|chunk| {
-                // convert the chunk (of type [u8; block_size]) to block_size (es: u64, u128)
-                let plain_block = C::Block::from_bytes(chunk);
-                // encrypts the block and converts it back to a Vec<u8>
-                cipher.encrypt_block(plain_block).to_bytes()
-            })
-            .flatten()
-            .collect()
+            .map(C::Block::from_bytes)
+            .collect::<Result<Vec<_>>>()?;
+
+        // blocks are independent: encrypt all of them in a single batch
+        cipher.encrypt_blocks(&mut blocks);
+
+        Ok(blocks.iter().flat_map(|block| block.to_bytes()).collect())
     }
 
-    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Vec<u8> {
-        // assert that ciphertext is a multiple of cipher's block size
-        assert!(
-            ciphertext.len() % C::Block::SIZE == 0,
-            "Ciphertext must be a multiple of block size"
-        );
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that ciphertext is a multiple of cipher's block size
+        check_block_aligned(ciphertext, C::Block::SIZE)?;
         
-        ciphertext
+        let mut blocks = ciphertext
             .chunks(C::Block::SIZE)
-            .map(|chunk| {
-                let cipher_block = C::Block::from_bytes(chunk);
-                cipher.decrypt_block(cipher_block).to_bytes()
-            })
-            .flatten()
-            .collect()
+            .map(C::Block::from_bytes)
+            .collect::<Result<Vec<_>>>()?;
+
+        cipher.decrypt_blocks(&mut blocks);
+
+        Ok(blocks.iter().flat_map(|block| block.to_bytes()).collect())
+    }
+}
+
+// blocks are independent: nothing to carry over
+impl<C: BlockCipher> StreamingMode<C> for ECB {
+    fn advance(&self, _input: &[u8], _output: &[u8], _decrypt: bool) -> Result<Self> {
+        Ok(ECB)
     }
 }
@@ -1,2 +1,17 @@
 pub mod ecb;
 pub mod cbc;
+pub mod ctr;
+pub mod cfb;
+pub mod ofb;
+pub mod pcbc;
+pub mod parallel;
+pub mod streaming;
+
+pub use ecb::ECB;
+pub use cbc::CBC;
+pub use ctr::CTR;
+pub use cfb::CFB;
+pub use ofb::OFB;
+pub use pcbc::PCBC;
+pub use parallel::Parallel;
+pub use streaming::{CipherReader, CipherWriter, Decryptor, Encryptor, StreamingMode, Transform};
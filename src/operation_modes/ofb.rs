@@ -0,0 +1,98 @@
+/*
+* Output Feedback mode (NIST SP 800-38A)
+*
+* the cipher is repeatedly applied to the IV to produce a keystream
+* that doesn't depend on the plaintext:
+*   O_0 = IV
+*   O_i = E(O_i-1)
+*   C_i = P_i XOR O_i
+* encryption and decryption are the same operation
+* and the input can have any length (the last keystream block is truncated)
+*/
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Result};
+use crate::operation_modes::streaming::StreamingMode;
+
+pub struct OFB<C: BlockCipher> {
+    pub iv: C::Block
+}
+
+impl<C: BlockCipher> OFB<C> {
+    // applies the keystream to input, used both to encrypt and decrypt
+    fn apply_keystream(&self, cipher: &C, input: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(input.len());
+        let mut feedback = self.iv;
+        for chunk in input.chunks(C::Block::SIZE) {
+            // the next keystream block is the encryption of the previous one
+            feedback = cipher.encrypt_block(feedback);
+            out.extend(chunk.iter().zip(feedback.to_bytes()).map(|(b, k)| b ^ k));
+        }
+
+        Ok(out)
+    }
+}
+
+impl<C: BlockCipher> CipherOperationMode<C> for OFB<C> {
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, plaintext)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, ciphertext)
+    }
+}
+
+// the next keystream block is the encryption of the last one, recovered as P_n XOR C_n
+impl<C: BlockCipher> StreamingMode<C> for OFB<C> {
+    fn advance(&self, input: &[u8], output: &[u8], _decrypt: bool) -> Result<Self> {
+        let len = input.len();
+        if len == 0 {
+            return Ok(Self { iv: self.iv });
+        }
+        let last = len - C::Block::SIZE;
+        let iv = C::Block::from_bytes(&input[last..])? ^ C::Block::from_bytes(&output[last..])?;
+        Ok(Self { iv })
+    }
+}
+
+#[test]
+// NIST SP 800-38A F.4.1 (OFB-AES128)
+fn test_ofb_aes_known_vector() {
+    use crate::AES;
+
+    let cipher = AES::new(&0x2b7e151628aed2a6abf7158809cf4f3cu128.to_be_bytes()).unwrap();
+    let ofb = OFB::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let plaintext = [
+        0x6bc1bee22e409f96e93d7e117393172au128.to_be_bytes(),
+        0xae2d8a571e03ac9c9eb76fac45af8e51u128.to_be_bytes(),
+    ].concat();
+    let expected = [
+        0x3b3fd92eb72dad20333449f8e83cfb4au128.to_be_bytes(),
+        0x7789508d16918f03f53c52dac54ed825u128.to_be_bytes(),
+    ].concat();
+
+    let ciphertext = ofb.encrypt(&cipher, &plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "OFB-AES128 failed encryption");
+    assert_eq!(ofb.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "OFB-AES128 failed decryption");
+}
+
+#[test]
+// FIPS 81 Appendix B (64 bit OFB)
+fn test_ofb_des_known_vector() {
+    use crate::DES;
+
+    let cipher = DES::new(0x0123456789abcdef);
+    let ofb = OFB::<DES> { iv: 0x1234567890abcdef };
+    let plaintext = b"Now is the time for all ";
+    let expected: Vec<u8> = [0xf3096249c7f46e51u64, 0x35f24a242eeb3d3f, 0x3d6d5be3255af8c3]
+        .iter()
+        .flat_map(|b| b.to_be_bytes())
+        .collect();
+
+    let ciphertext = ofb.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(ciphertext, expected, "OFB-DES failed encryption");
+    assert_eq!(ofb.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "OFB-DES failed decryption");
+
+    // truncated input uses a truncated keystream
+    assert_eq!(ofb.encrypt(&cipher, &plaintext[..13]).unwrap(), expected[..13]);
+}
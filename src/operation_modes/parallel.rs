@@ -0,0 +1,254 @@
+/*
+* Opt-in multithreaded execution of the parallelizable modes
+*
+* Parallel<M> wraps a mode and splits the input into chunks of chunk_blocks blocks,
+* handed out round robin to workers scoped threads, each running the serial mode on its chunks:
+* - ECB: encryption and decryption, every block is independent
+* - CBC: decryption only, P_i = D(C_i) XOR C_{i-1} so a chunk just needs
+*   the last ciphertext block of the previous one as its IV (encryption stays serial)
+* - CTR: encryption and decryption, chunk k starts k * chunk_blocks counter values later
+*
+* NOTE the output (and any error) is byte-identical to the serial mode
+*/
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Error, Result};
+use crate::error::check_block_aligned;
+use crate::operation_modes::{CBC, CTR, ECB};
+
+// default number of blocks in a chunk
+const DEFAULT_CHUNK_BLOCKS: usize = 4096;
+
+pub struct Parallel<M> {
+    // the wrapped (serial) mode
+    mode: M,
+    // number of threads
+    workers: usize,
+    // blocks processed by a thread at a time
+    chunk_blocks: usize,
+}
+
+impl<M> Parallel<M> {
+    // one worker per available core, 4096 blocks per chunk
+    pub fn new(mode: M) -> Self {
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self { mode, workers, chunk_blocks: DEFAULT_CHUNK_BLOCKS }
+    }
+
+    // explicit number of workers and chunk size (in blocks)
+    pub fn with_config(mode: M, workers: usize, chunk_blocks: usize) -> Result<Self> {
+        if workers == 0 {
+            return Err(Error::InvalidParameter("at least one worker is needed"));
+        }
+        if chunk_blocks == 0 {
+            return Err(Error::InvalidParameter("chunks must contain at least one block"));
+        }
+        Ok(Self { mode, workers, chunk_blocks })
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    pub fn chunk_blocks(&self) -> usize {
+        self.chunk_blocks
+    }
+
+    // the wrapped mode
+    pub fn mode(&self) -> &M {
+        &self.mode
+    }
+
+    // chunk size in bytes, saturating: a chunk larger than any input is just a single chunk,
+    // so k * chunk_size (and k * chunk_blocks) never overflows for an existing chunk k
+    fn chunk_size(&self, block_size: usize) -> usize {
+        self.chunk_blocks.saturating_mul(block_size)
+    }
+
+    // runs job(k, chunk_k) for every chunk on the worker threads and concatenates the outputs in order
+    // every job must return exactly as many bytes as its chunk
+    fn run<F>(&self, input: &[u8], block_size: usize, job: F) -> Result<Vec<u8>>
+    where
+        F: Fn(usize, &[u8]) -> Result<Vec<u8>> + Sync,
+    {
+        let chunks: Vec<&[u8]> = input.chunks(self.chunk_size(block_size)).collect();
+
+        // nothing to split: stay on the current thread
+        if chunks.len() <= 1 || self.workers == 1 {
+            let out = chunks
+                .iter()
+                .enumerate()
+                .map(|(k, chunk)| job(k, chunk))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(out.concat());
+        }
+
+        // worker w gets chunks w, w + workers, w + 2 * workers, ...
+        let workers = self.workers.min(chunks.len());
+        let mut results: Vec<Option<Result<Vec<u8>>>> = (0..chunks.len()).map(|_| None).collect();
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|w| {
+                    let (chunks, job) = (&chunks, &job);
+                    scope.spawn(move || {
+                        (w..chunks.len())
+                            .step_by(workers)
+                            .map(|k| (k, job(k, chunks[k])))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                for (k, result) in handle.join().expect("worker thread panicked") {
+                    results[k] = Some(result);
+                }
+            }
+        });
+
+        // the first failing chunk decides the error, as in the serial mode
+        let mut out = Vec::with_capacity(input.len());
+        for result in results {
+            out.extend(result.expect("every chunk is processed")?);
+        }
+        Ok(out)
+    }
+}
+
+impl<C> CipherOperationMode<C> for Parallel<ECB>
+where
+    C: BlockCipher + Sync,
+{
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        check_block_aligned(plaintext, C::Block::SIZE)?;
+        self.run(plaintext, C::Block::SIZE, |_, chunk| self.mode.encrypt(cipher, chunk))
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        check_block_aligned(ciphertext, C::Block::SIZE)?;
+        self.run(ciphertext, C::Block::SIZE, |_, chunk| self.mode.decrypt(cipher, chunk))
+    }
+}
+
+impl<C> CipherOperationMode<C> for Parallel<CBC<C>>
+where
+    C: BlockCipher + Sync,
+    C::Block: Sync,
+{
+    // every block depends on the previous one: serial
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.mode.encrypt(cipher, plaintext)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        check_block_aligned(ciphertext, C::Block::SIZE)?;
+
+        let chunk_size = self.chunk_size(C::Block::SIZE);
+        self.run(ciphertext, C::Block::SIZE, |k, chunk| {
+            // the IV of chunk k is the last ciphertext block of chunk k - 1
+            let iv = match k {
+                0 => self.mode.iv,
+                _ => C::Block::from_bytes(&ciphertext[k * chunk_size - C::Block::SIZE..k * chunk_size])?,
+            };
+            CBC::<C> { iv }.decrypt(cipher, chunk)
+        })
+    }
+}
+
+impl<C> CipherOperationMode<C> for Parallel<CTR<C>>
+where
+    C: BlockCipher + Sync,
+    C::Block: Sync,
+{
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, plaintext)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.apply_keystream(cipher, ciphertext)
+    }
+}
+
+impl<C> Parallel<CTR<C>>
+where
+    C: BlockCipher + Sync,
+    C::Block: Sync,
+{
+    fn apply_keystream(&self, cipher: &C, input: &[u8]) -> Result<Vec<u8>> {
+        // validate up front as the serial mode does: an empty input never reaches a job
+        self.mode.check_counter_bits()?;
+        self.run(input, C::Block::SIZE, |k, chunk| {
+            // chunk k starts k * chunk_blocks counter values later
+            self.mode.skip((k * self.chunk_blocks) as u128)?.encrypt(cipher, chunk)
+        })
+    }
+}
+
+#[test]
+// every configuration must match the serial modes byte by byte
+fn test_parallel_matches_serial() {
+    use crate::{AES, DES};
+
+    let des = DES::new(0x133457799BBCDFF1);
+    let aes = AES::new(&[0x2b; 16]).unwrap();
+    let data: Vec<u8> = (0..16 * 1000).map(|i| (i * 31 + 7) as u8).collect();
+
+    for (workers, chunk_blocks) in [(1, 1), (2, 1), (3, 7), (4, 64), (8, 1000), (16, 5000)] {
+        let ecb = Parallel::with_config(ECB, workers, chunk_blocks).unwrap();
+        let ciphertext = ecb.encrypt(&des, &data).unwrap();
+        assert_eq!(ciphertext, ECB.encrypt(&des, &data).unwrap(), "ECB {} {}", workers, chunk_blocks);
+        assert_eq!(ecb.decrypt(&des, &ciphertext).unwrap(), data);
+
+        let cbc = CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+        let ciphertext = cbc.encrypt(&aes, &data).unwrap();
+        let parallel = Parallel::with_config(CBC::<AES> { iv: cbc.iv }, workers, chunk_blocks).unwrap();
+        assert_eq!(parallel.decrypt(&aes, &ciphertext).unwrap(), data, "CBC {} {}", workers, chunk_blocks);
+        assert_eq!(parallel.encrypt(&aes, &data).unwrap(), ciphertext);
+
+        // CTR works on any length
+        let ctr = CTR::<AES>::new(&[0xAB; 12], 32).unwrap();
+        let parallel = Parallel::with_config(CTR::<AES>::new(&[0xAB; 12], 32).unwrap(), workers, chunk_blocks).unwrap();
+        let ciphertext = parallel.encrypt(&aes, &data[..data.len() - 5]).unwrap();
+        assert_eq!(ciphertext, ctr.encrypt(&aes, &data[..data.len() - 5]).unwrap(), "CTR {} {}", workers, chunk_blocks);
+        assert_eq!(parallel.decrypt(&aes, &ciphertext).unwrap(), data[..data.len() - 5]);
+    }
+
+    // chunks too big to be measured in bytes are a single chunk
+    let ecb = Parallel::with_config(ECB, 4, usize::MAX).unwrap();
+    assert_eq!(ecb.encrypt(&des, &data).unwrap(), ECB.encrypt(&des, &data).unwrap());
+    let cbc = CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let parallel = Parallel::with_config(CBC::<AES> { iv: cbc.iv }, 4, usize::MAX / 8).unwrap();
+    assert_eq!(parallel.decrypt(&aes, &cbc.encrypt(&aes, &data).unwrap()).unwrap(), data);
+
+    // default configuration
+    let ecb = Parallel::new(ECB);
+    assert!(ecb.workers() >= 1);
+    assert_eq!(ecb.encrypt(&des, &data).unwrap(), ECB.encrypt(&des, &data).unwrap());
+}
+
+#[test]
+fn test_parallel_errors() {
+    use crate::DES;
+
+    let des = DES::new(0x133457799BBCDFF1);
+    assert!(matches!(Parallel::with_config(ECB, 0, 1), Err(Error::InvalidParameter(_))));
+    assert!(matches!(Parallel::with_config(ECB, 1, 0), Err(Error::InvalidParameter(_))));
+
+    // same errors as the serial modes
+    let ecb = Parallel::with_config(ECB, 4, 2).unwrap();
+    assert_eq!(ecb.encrypt(&des, &[0u8; 61]), ECB.encrypt(&des, &[0u8; 61]));
+
+    // only 3 counter values left: the overflow is detected in whatever chunk it happens
+    let ctr = CTR::<DES> { counter_block: 0x00000000000000FD, counter_bits: 8 };
+    let parallel = Parallel::with_config(CTR::<DES> { counter_block: 0xFD, counter_bits: 8 }, 4, 1).unwrap();
+    assert_eq!(parallel.encrypt(&des, &[0u8; 25]), Err(Error::CounterOverflow));
+    assert_eq!(parallel.encrypt(&des, &[0u8; 24]).unwrap(), ctr.encrypt(&des, &[0u8; 24]).unwrap());
+
+    // an invalid counter is rejected even without any input
+    let ctr = CTR::<DES> { counter_block: 0, counter_bits: 0 };
+    let parallel = Parallel::with_config(CTR::<DES> { counter_block: 0, counter_bits: 0 }, 4, 1).unwrap();
+    assert_eq!(parallel.encrypt(&des, &[]), ctr.encrypt(&des, &[]));
+    assert!(matches!(parallel.decrypt(&des, &[]), Err(Error::InvalidParameter(_))));
+}
@@ -0,0 +1,109 @@
+/*
+* Propagating Cipher Block Chaining mode (used by Kerberos v4)
+*
+*   C_i = E(P_i XOR P_i-1 XOR C_i-1)
+*   P_i = D(C_i) XOR P_i-1 XOR C_i-1
+* with P_0 XOR C_0 = IV
+*
+* unlike CBC an error in a ciphertext block propagates to all the following plaintext blocks
+*/
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Result};
+use crate::error::check_block_aligned;
+use crate::operation_modes::streaming::StreamingMode;
+
+pub struct PCBC<C: BlockCipher> {
+    pub iv: C::Block
+}
+
+impl<C: BlockCipher> CipherOperationMode<C> for PCBC<C> {
+    fn encrypt(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that plaintext is a multiple of the cipher's block size
+        check_block_aligned(plaintext, C::Block::SIZE)?;
+
+        let mut out = Vec::new();
+        let mut prev = self.iv;
+        for chunk in plaintext.chunks(C::Block::SIZE) {
+            let plain_block = C::Block::from_bytes(chunk)?;
+            let cipher_block = cipher.encrypt_block(plain_block ^ prev);
+            // the next block is chained with both P_i and C_i
+            prev = plain_block ^ cipher_block;
+            out.append(&mut cipher_block.to_bytes());
+        }
+
+        Ok(out)
+    }
+
+    fn decrypt(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        // make sure that ciphertext is a multiple of the cipher's block size
+        check_block_aligned(ciphertext, C::Block::SIZE)?;
+
+        let mut out = Vec::new();
+        let mut prev = self.iv;
+        for chunk in ciphertext.chunks(C::Block::SIZE) {
+            let cipher_block = C::Block::from_bytes(chunk)?;
+            let plain_block = cipher.decrypt_block(cipher_block) ^ prev;
+            prev = plain_block ^ cipher_block;
+            out.append(&mut plain_block.to_bytes());
+        }
+
+        Ok(out)
+    }
+}
+
+// the IV of the next piece is P_n XOR C_n of the last block
+impl<C: BlockCipher> StreamingMode<C> for PCBC<C> {
+    fn advance(&self, input: &[u8], output: &[u8], _decrypt: bool) -> Result<Self> {
+        let len = input.len();
+        if len == 0 {
+            return Ok(Self { iv: self.iv });
+        }
+        let last = len - C::Block::SIZE;
+        let iv = C::Block::from_bytes(&input[last..])? ^ C::Block::from_bytes(&output[last..])?;
+        Ok(Self { iv })
+    }
+}
+
+#[test]
+fn test_pcbc_des() {
+    use crate::{DES, CBC};
+
+    let cipher = DES::new(0x0123456789abcdef);
+    let iv = 0x1234567890abcdef;
+    let pcbc = PCBC::<DES> { iv };
+    let plaintext = b"Now is the time for all ";
+
+    let ciphertext = pcbc.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(pcbc.decrypt(&cipher, &ciphertext).unwrap(), plaintext, "PCBC-DES failed decryption");
+
+    // the first block is the same as CBC (FIPS 81 Appendix B)
+    assert_eq!(ciphertext[..8], 0xe5c7cdde872bf27cu64.to_be_bytes());
+
+    // the following blocks are CBC with P_i-1 XOR C_i-1 as IV
+    for i in 1..3 {
+        let chained_iv = u64::from_bytes(&plaintext[8 * (i - 1)..8 * i]).unwrap()
+            ^ u64::from_bytes(&ciphertext[8 * (i - 1)..8 * i]).unwrap();
+        let cbc = CBC::<DES> { iv: chained_iv };
+        assert_eq!(cbc.encrypt(&cipher, &plaintext[8 * i..8 * (i + 1)]).unwrap(), ciphertext[8 * i..8 * (i + 1)]);
+    }
+
+    // corrupting a block garbles every following plaintext block
+    let mut tampered = ciphertext.clone();
+    tampered[0] ^= 1;
+    let decrypted = pcbc.decrypt(&cipher, &tampered).unwrap();
+    for i in 0..3 {
+        assert_ne!(decrypted[8 * i..8 * (i + 1)], plaintext[8 * i..8 * (i + 1)]);
+    }
+}
+
+#[test]
+fn test_pcbc_aes_roundtrip() {
+    use crate::AES;
+
+    let cipher = AES::new(&[0x2b; 16]).unwrap();
+    let pcbc = PCBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let plaintext = [0x42u8; 64];
+
+    let ciphertext = pcbc.encrypt(&cipher, &plaintext).unwrap();
+    assert_eq!(pcbc.decrypt(&cipher, &ciphertext).unwrap(), plaintext);
+}
@@ -0,0 +1,409 @@
+/*
+* Incremental (streaming) encryption and decryption
+*
+* CipherOperationMode works on the whole message at once, an Encryptor/Decryptor
+* instead accepts the message in pieces of any size with update() and returns
+* every output byte that can already be computed, finalize() flushes the rest:
+* - input is buffered until a whole unit is available (a block, or a CFB segment)
+* - every unit aligned piece goes through the serial mode, then the mode is
+*   advanced past it (es: CBC's IV becomes the last ciphertext block)
+* - with a padding, the Encryptor pads the last piece and the Decryptor
+*   holds back the last block until finalize() to strip the padding
+*
+* CipherWriter and CipherReader wrap an Encryptor/Decryptor into io::Write and io::Read
+*
+* NOTE the output is byte-identical to the one-shot modes (and Padded) whatever the pieces are
+*/
+
+use std::io::{self, Read, Write};
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Error, Result};
+use crate::padding::Padding;
+
+// modes that can be resumed after a unit aligned piece of the message
+pub trait StreamingMode<C: BlockCipher>: CipherOperationMode<C> + Sized {
+    // the serial mode is only run on multiples of this many bytes (except at finalize)
+    fn unit_size(&self) -> usize {
+        C::Block::SIZE
+    }
+
+    // the mode continuing after input was turned into output
+    fn advance(&self, input: &[u8], output: &[u8], decrypt: bool) -> Result<Self>;
+}
+
+// incremental encryption or decryption
+pub trait Transform {
+    // processes a piece of the message, returning the output available so far
+    fn update(&mut self, data: &[u8]) -> Result<Vec<u8>>;
+
+    // processes whatever is left
+    fn finalize(self) -> Result<Vec<u8>>;
+}
+
+pub struct Encryptor<'a, C: BlockCipher, M: StreamingMode<C>> {
+    core: Core<'a, C, M>,
+}
+
+pub struct Decryptor<'a, C: BlockCipher, M: StreamingMode<C>> {
+    core: Core<'a, C, M>,
+}
+
+impl<'a, C: BlockCipher, M: StreamingMode<C>> Encryptor<'a, C, M> {
+    pub fn new(cipher: &'a C, mode: M) -> Self {
+        Self { core: Core::new(cipher, mode, None, false) }
+    }
+
+    // pads the message at finalize, like Padded::encrypt
+    pub fn with_padding(cipher: &'a C, mode: M, padding: impl Padding + 'static) -> Self {
+        Self { core: Core::new(cipher, mode, Some(Box::new(padding)), false) }
+    }
+}
+
+impl<C: BlockCipher, M: StreamingMode<C>> Transform for Encryptor<'_, C, M> {
+    fn update(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+        self.core.update(data, 0)
+    }
+
+    fn finalize(self) -> Result<Vec<u8>> {
+        let core = self.core;
+        match &core.padding {
+            Some(padding) => core.process(&padding.pad(&core.buffer, C::Block::SIZE)?),
+            None => core.process(&core.buffer),
+        }
+    }
+}
+
+impl<'a, C: BlockCipher, M: StreamingMode<C>> Decryptor<'a, C, M> {
+    pub fn new(cipher: &'a C, mode: M) -> Self {
+        Self { core: Core::new(cipher, mode, None, true) }
+    }
+
+    // strips the padding at finalize, like Padded::decrypt
+    pub fn with_padding(cipher: &'a C, mode: M, padding: impl Padding + 'static) -> Self {
+        Self { core: Core::new(cipher, mode, Some(Box::new(padding)), true) }
+    }
+}
+
+impl<C: BlockCipher, M: StreamingMode<C>> Transform for Decryptor<'_, C, M> {
+    fn update(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+        // the last block may be padding: keep it until finalize
+        let hold_back = if self.core.padding.is_some() { C::Block::SIZE } else { 0 };
+        self.core.update(data, hold_back)
+    }
+
+    fn finalize(self) -> Result<Vec<u8>> {
+        let core = self.core;
+        let total = core.len + core.buffer.len();
+        let out = core.process(&core.buffer)?;
+        match &core.padding {
+            Some(padding) => padding
+                .unpad(&out, C::Block::SIZE)
+                .map(<[u8]>::to_vec)
+                .map_err(|e| with_total_len(e, total)),
+            None => Ok(out),
+        }
+    }
+}
+
+// state shared by Encryptor and Decryptor
+struct Core<'a, C: BlockCipher, M: StreamingMode<C>> {
+    cipher: &'a C,
+    // the mode, advanced past everything processed so far
+    mode: M,
+    // input waiting for a whole unit
+    buffer: Vec<u8>,
+    // bytes processed so far
+    len: usize,
+    // set if the mode can't go any further (es: CTR used all its counter values),
+    // reported only if more input arrives
+    exhausted: Option<Error>,
+    padding: Option<Box<dyn Padding>>,
+    decrypt: bool,
+}
+
+impl<'a, C: BlockCipher, M: StreamingMode<C>> Core<'a, C, M> {
+    fn new(cipher: &'a C, mode: M, padding: Option<Box<dyn Padding>>, decrypt: bool) -> Self {
+        Self { cipher, mode, buffer: Vec::new(), len: 0, exhausted: None, padding, decrypt }
+    }
+
+    // processes every whole unit, keeping at least hold_back bytes in the buffer
+    // NOTE on error nothing of data is consumed, the state is the one before the call
+    fn update(&mut self, data: &[u8], hold_back: usize) -> Result<Vec<u8>> {
+        let buffered = self.buffer.len();
+        self.buffer.extend_from_slice(data);
+
+        let unit = self.mode.unit_size().max(1);
+        let ready = self.buffer.len().saturating_sub(hold_back) / unit * unit;
+        if ready == 0 {
+            return Ok(Vec::new());
+        }
+
+        let output = match self.process(&self.buffer[..ready]) {
+            Ok(output) => output,
+            Err(e) => {
+                self.buffer.truncate(buffered);
+                return Err(e);
+            }
+        };
+
+        // continue after the unit aligned input
+        match self.mode.advance(&self.buffer[..ready], &output, self.decrypt) {
+            Ok(mode) => self.mode = mode,
+            Err(e) => self.exhausted = Some(e),
+        }
+        self.buffer.drain(..ready);
+        self.len += ready;
+        Ok(output)
+    }
+
+    // runs the serial mode on input (the mode isn't advanced)
+    fn process(&self, input: &[u8]) -> Result<Vec<u8>> {
+        if let (Some(e), false) = (&self.exhausted, input.is_empty()) {
+            return Err(e.clone());
+        }
+        let output = match self.decrypt {
+            false => self.mode.encrypt(self.cipher, input),
+            true => self.mode.decrypt(self.cipher, input),
+        };
+        output.map_err(|e| with_total_len(e, self.len + input.len()))
+    }
+}
+
+// misalignment errors report the length of the whole message, as the one-shot modes do
+fn with_total_len(error: Error, total: usize) -> Error {
+    match error {
+        Error::NotBlockAligned { block_size, .. } => Error::NotBlockAligned { block_size, len: total },
+        error => error,
+    }
+}
+
+// io::Write adapter: everything written is transformed and written to the inner writer
+pub struct CipherWriter<T: Transform, W: Write> {
+    // None once finished
+    transform: Option<T>,
+    inner: W,
+}
+
+impl<T: Transform, W: Write> CipherWriter<T, W> {
+    pub fn new(transform: T, inner: W) -> Self {
+        Self { transform: Some(transform), inner }
+    }
+
+    // finalizes the transform, writes the last bytes and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(transform) = self.transform.take() {
+            self.inner.write_all(&transform.finalize()?)?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<T: Transform, W: Write> Write for CipherWriter<T, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let transform = self.transform.as_mut().ok_or_else(|| io::Error::other("writer already finished"))?;
+        let out = transform.update(buf)?;
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    // NOTE a partial block can't be flushed before finish()
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// io::Read adapter: reads from the inner reader and returns the transformed bytes
+pub struct CipherReader<T: Transform, R: Read> {
+    // None once the inner reader is exhausted and the transform finalized
+    transform: Option<T>,
+    inner: R,
+    // transformed bytes not returned yet
+    pending: Vec<u8>,
+    position: usize,
+}
+
+// bytes read from the inner reader at a time
+const READ_CHUNK_SIZE: usize = 8192;
+
+impl<T: Transform, R: Read> CipherReader<T, R> {
+    pub fn new(transform: T, inner: R) -> Self {
+        Self { transform: Some(transform), inner, pending: Vec::new(), position: 0 }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<T: Transform, R: Read> Read for CipherReader<T, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0u8; READ_CHUNK_SIZE];
+
+        // refill until there is something to return or everything was finalized
+        while self.position == self.pending.len() {
+            let Some(transform) = self.transform.as_mut() else {
+                return Ok(0);
+            };
+
+            let n = self.inner.read(&mut chunk)?;
+            self.pending = match n {
+                0 => self.transform.take().expect("checked above").finalize()?,
+                _ => transform.update(&chunk[..n])?,
+            };
+            self.position = 0;
+        }
+
+        let n = buf.len().min(self.pending.len() - self.position);
+        buf[..n].copy_from_slice(&self.pending[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+#[test]
+// any split of the message must give the one-shot output, in every mode
+fn test_streaming_matches_one_shot() {
+    use crate::{AES, DES};
+    use crate::operation_modes::{CBC, CFB, CTR, ECB, OFB, PCBC};
+
+    fn check<C: BlockCipher, M: StreamingMode<C>>(cipher: &C, mode: impl Fn() -> M, message: &[u8]) {
+        let ciphertext = mode().encrypt(cipher, message).unwrap();
+        for piece in [1, 3, 7, 8, 16, 17, 100, message.len()] {
+            let mut encryptor = Encryptor::new(cipher, mode());
+            let mut decryptor = Decryptor::new(cipher, mode());
+            let (mut encrypted, mut decrypted) = (Vec::new(), Vec::new());
+            for (plain, encrypted_piece) in message.chunks(piece).zip(ciphertext.chunks(piece)) {
+                encrypted.extend(encryptor.update(plain).unwrap());
+                decrypted.extend(decryptor.update(encrypted_piece).unwrap());
+            }
+            encrypted.extend(encryptor.finalize().unwrap());
+            decrypted.extend(decryptor.finalize().unwrap());
+            assert_eq!(encrypted, ciphertext, "pieces of {} bytes", piece);
+            assert_eq!(decrypted, message, "pieces of {} bytes", piece);
+        }
+    }
+
+    let des = DES::new(0x133457799BBCDFF1);
+    let aes = AES::new(&[0x2b; 16]).unwrap();
+    let aligned: Vec<u8> = (0..16 * 20).map(|i| (i * 13 + 5) as u8).collect();
+    let unaligned = &aligned[..16 * 20 - 3];
+
+    check(&des, || ECB, &aligned);
+    check(&aes, || CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f }, &aligned);
+    check(&des, || PCBC::<DES> { iv: 0x1234567890abcdef }, &aligned);
+    check(&aes, || CTR::<AES>::new(&[0xAB; 12], 32).unwrap(), unaligned);
+    check(&des, || OFB::<DES> { iv: 0x1234567890abcdef }, unaligned);
+    check(&aes, || CFB::<AES>::new(0x000102030405060708090a0b0c0d0e0f), unaligned);
+    check(&des, || CFB::<DES>::cfb8(0x1234567890abcdef), unaligned);
+    check(&des, || CFB::<DES> { iv: 0x1234567890abcdef, segment_size: 3 }, unaligned);
+}
+
+#[test]
+fn test_streaming_padding_and_errors() {
+    use crate::DES;
+    use crate::operation_modes::{CBC, CTR, ECB};
+    use crate::padding::{Padded, PKCS7, ZeroPadding};
+
+    let des = DES::new(0x133457799BBCDFF1);
+    let cbc = || CBC::<DES> { iv: 0x0123456789ABCDEF };
+
+    for len in [0, 1, 7, 8, 9, 30, 32] {
+        let message: Vec<u8> = (1..=len as u8).collect();
+        let ciphertext = Padded::new(cbc(), PKCS7).encrypt(&des, &message).unwrap();
+
+        let mut encryptor = Encryptor::with_padding(&des, cbc(), PKCS7);
+        let mut encrypted = Vec::new();
+        for byte in &message {
+            encrypted.extend(encryptor.update(&[*byte]).unwrap());
+        }
+        encrypted.extend(encryptor.finalize().unwrap());
+        assert_eq!(encrypted, ciphertext, "{} bytes", len);
+
+        let mut decryptor = Decryptor::with_padding(&des, cbc(), PKCS7);
+        let mut decrypted = decryptor.update(&ciphertext).unwrap();
+        // the last block is held back until finalize
+        assert_eq!(decrypted.len(), ciphertext.len() - 8);
+        decrypted.extend(decryptor.finalize().unwrap());
+        assert_eq!(decrypted, message, "{} bytes", len);
+    }
+
+    // zero padding only looks at the last block, as the one-shot version
+    // (a whole block of zeros is rejected by both)
+    let padded = Padded::new(ECB, ZeroPadding);
+    for message in [&b"ends with zero\0\0"[..], &b"ends with zeros\0\0\0"[..]] {
+        let ciphertext = padded.encrypt(&des, message).unwrap();
+        let mut decryptor = Decryptor::with_padding(&des, ECB, ZeroPadding);
+        let decrypted = decryptor.update(&ciphertext).unwrap();
+        let decrypted = decryptor.finalize().map(|last| [decrypted, last].concat());
+        assert_eq!(decrypted, padded.decrypt(&des, &ciphertext));
+    }
+
+    // same errors as the one-shot modes
+    let mut encryptor = Encryptor::new(&des, ECB);
+    encryptor.update(&[0u8; 13]).unwrap();
+    encryptor.update(&[0u8; 12]).unwrap();
+    assert_eq!(encryptor.finalize(), ECB.encrypt(&des, &[0u8; 25]));
+
+    let mut decryptor = Decryptor::with_padding(&des, cbc(), PKCS7);
+    decryptor.update(&[0u8; 16]).unwrap();
+    assert_eq!(decryptor.finalize(), Padded::new(cbc(), PKCS7).decrypt(&des, &[0u8; 16]));
+
+    let ctr = || CTR::<DES> { counter_block: 0xFE, counter_bits: 8 };
+    let mut encryptor = Encryptor::new(&des, ctr());
+    assert_eq!(encryptor.update(&[0u8; 16]), ctr().encrypt(&des, &[0u8; 16]));
+    assert_eq!(encryptor.update(&[0u8; 1]), Ok(Vec::new()));
+    assert_eq!(encryptor.finalize(), Err(Error::CounterOverflow));
+
+    // a failed update doesn't consume any of its input
+    let mut encryptor = Encryptor::new(&des, CTR::<DES> { counter_block: 0xFF, counter_bits: 8 });
+    encryptor.update(&[0u8; 8]).unwrap();
+    encryptor.update(&[1, 2, 3]).unwrap();
+    assert_eq!(encryptor.update(&[4u8; 8]), Err(Error::CounterOverflow));
+    assert_eq!(encryptor.core.buffer, [1, 2, 3]);
+    assert_eq!(encryptor.core.len, 8);
+}
+
+#[test]
+fn test_io_adapters() {
+    use crate::AES;
+    use crate::operation_modes::{CBC, CTR};
+    use crate::padding::{Padded, PKCS7};
+
+    let aes = AES::new(&[0x2b; 16]).unwrap();
+    let cbc = || CBC::<AES> { iv: 0x000102030405060708090a0b0c0d0e0f };
+    let message: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+    let expected = Padded::new(cbc(), PKCS7).encrypt(&aes, &message).unwrap();
+
+    // io::copy through the writer
+    let mut writer = CipherWriter::new(Encryptor::with_padding(&aes, cbc(), PKCS7), Vec::new());
+    io::copy(&mut &message[..], &mut writer).unwrap();
+    let ciphertext = writer.finish().unwrap();
+    assert_eq!(ciphertext, expected);
+
+    // small reads through the reader
+    let mut reader = CipherReader::new(Decryptor::with_padding(&aes, cbc(), PKCS7), &ciphertext[..]);
+    let mut decrypted = Vec::new();
+    let mut buf = [0u8; 100];
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        decrypted.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(decrypted, message);
+
+    // a failed write writes nothing, neither to the inner writer nor to the buffered input
+    let ctr = CTR::<AES> { counter_block: 0xFF, counter_bits: 8 };
+    let mut writer = CipherWriter::new(Encryptor::new(&aes, ctr), Vec::new());
+    assert_eq!(writer.write(&[0u8; 20]).unwrap(), 20);
+    assert_eq!(writer.write(&[0u8; 16]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert_eq!(writer.inner.len(), 16);
+    assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    // errors surface as io::ErrorKind::InvalidData
+    let mut reader = CipherReader::new(Decryptor::with_padding(&aes, cbc(), PKCS7), &ciphertext[..40]);
+    let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,266 @@
+/*
+* Padding schemes used to encrypt messages whose length
+* isn't a multiple of the cipher's block size
+*
+* - PKCS#7:        every padding byte is the padding length       (.. 04 04 04 04)
+* - ANSI X.923:    zeros followed by the padding length           (.. 00 00 00 04)
+* - ISO 10126:     random bytes followed by the padding length    (.. 3A F1 9C 04)
+* - ISO/IEC 7816-4: a single 0x80 byte followed by zeros          (.. 80 00 00 00)
+* - Zero padding:  zeros, only added if needed (ambiguous if the message ends with 0x00)
+*/
+
+use rand::Rng;
+
+use crate::{BlockType, BlockCipher, CipherOperationMode, Error, Result};
+use crate::error::check_block_aligned;
+
+// general trait for a padding scheme
+pub trait Padding {
+    // pads data to a multiple of block_size
+    // NOTE block_size must be in [1, 255] (the padding length has to fit in a byte)
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>>;
+
+    // strips the padding from data, failing if it is malformed
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]>;
+}
+
+pub struct PKCS7;
+pub struct AnsiX923;
+pub struct ISO10126;
+pub struct ISO7816;
+pub struct ZeroPadding;
+
+// number of padding bytes needed by data, always in [1, block_size]
+fn padding_len(data: &[u8], block_size: usize) -> Result<usize> {
+    if block_size == 0 || block_size > 255 {
+        return Err(Error::InvalidParameter("padding block size must be in [1, 255] bytes"));
+    }
+    Ok(block_size - data.len() % block_size)
+}
+
+// makes sure that padded data is a non empty multiple of block_size
+fn check_len(data: &[u8], block_size: usize) -> Result<()> {
+    check_block_aligned(data, block_size)?;
+    if data.is_empty() {
+        return Err(Error::InvalidPadding);
+    }
+    Ok(())
+}
+
+// reads the padding length from the last byte of data
+fn trailing_len(data: &[u8], block_size: usize) -> Result<usize> {
+    check_len(data, block_size)?;
+    let n = data[data.len() - 1] as usize;
+    if n == 0 || n > block_size {
+        return Err(Error::InvalidPadding);
+    }
+    Ok(n)
+}
+
+impl Padding for PKCS7 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>> {
+        let n = padding_len(data, block_size)?;
+        let mut out = data.to_vec();
+        out.resize(data.len() + n, n as u8);
+        Ok(out)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]> {
+        let n = trailing_len(data, block_size)?;
+        let (message, padding) = data.split_at(data.len() - n);
+        if padding.iter().any(|&b| b as usize != n) {
+            return Err(Error::InvalidPadding);
+        }
+        Ok(message)
+    }
+}
+
+impl Padding for AnsiX923 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>> {
+        let n = padding_len(data, block_size)?;
+        let mut out = data.to_vec();
+        out.resize(data.len() + n - 1, 0);
+        out.push(n as u8);
+        Ok(out)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]> {
+        let n = trailing_len(data, block_size)?;
+        let (message, padding) = data.split_at(data.len() - n);
+        if padding[..n - 1].iter().any(|&b| b != 0) {
+            return Err(Error::InvalidPadding);
+        }
+        Ok(message)
+    }
+}
+
+impl Padding for ISO10126 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>> {
+        let n = padding_len(data, block_size)?;
+        let mut rng = rand::rng();
+        let mut out = data.to_vec();
+        out.extend((0..n - 1).map(|_| rng.random::<u8>()));
+        out.push(n as u8);
+        Ok(out)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]> {
+        // the padding bytes are random, only the length can be checked
+        let n = trailing_len(data, block_size)?;
+        Ok(&data[..data.len() - n])
+    }
+}
+
+impl Padding for ISO7816 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>> {
+        let n = padding_len(data, block_size)?;
+        let mut out = data.to_vec();
+        out.push(0x80);
+        out.resize(data.len() + n, 0);
+        Ok(out)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]> {
+        check_len(data, block_size)?;
+        // the 0x80 marker must be within the last block, preceded only by zeros
+        let last_block = &data[data.len() - block_size..];
+        match last_block.iter().rposition(|&b| b != 0) {
+            Some(i) if last_block[i] == 0x80 => Ok(&data[..data.len() - block_size + i]),
+            _ => Err(Error::InvalidPadding),
+        }
+    }
+}
+
+impl Padding for ZeroPadding {
+    fn pad(&self, data: &[u8], block_size: usize) -> Result<Vec<u8>> {
+        let mut out = data.to_vec();
+        let n = padding_len(data, block_size)?;
+        if n != block_size {
+            out.resize(data.len() + n, 0);
+        }
+        Ok(out)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8]> {
+        check_block_aligned(data, block_size)?;
+        // NOTE: trailing zeros of the original message are stripped too
+        let end = data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        if data.len() - end >= block_size {
+            return Err(Error::InvalidPadding);
+        }
+        Ok(&data[..end])
+    }
+}
+
+// attaches a Padding to any CipherOperationMode
+// so that it accepts plaintexts of any length (es: Padded::new(ECB, PKCS7))
+pub struct Padded<M, P: Padding> {
+    pub mode: M,
+    pub padding: P,
+}
+
+impl<M, P: Padding> Padded<M, P> {
+    pub fn new(mode: M, padding: P) -> Self {
+        Self { mode, padding }
+    }
+
+    // pads the plaintext and encrypts it using the wrapped mode
+    pub fn encrypt<C>(&self, cipher: &C, plaintext: &[u8]) -> Result<Vec<u8>>
+    where
+        C: BlockCipher,
+        M: CipherOperationMode<C>,
+    {
+        let padded = self.padding.pad(plaintext, C::Block::SIZE)?;
+        self.mode.encrypt(cipher, &padded)
+    }
+
+    // decrypts the ciphertext using the wrapped mode and strips the padding
+    pub fn decrypt<C>(&self, cipher: &C, ciphertext: &[u8]) -> Result<Vec<u8>>
+    where
+        C: BlockCipher,
+        M: CipherOperationMode<C>,
+    {
+        let padded = self.mode.decrypt(cipher, ciphertext)?;
+        Ok(self.padding.unpad(&padded, C::Block::SIZE)?.to_vec())
+    }
+}
+
+#[test]
+fn test_padding_known_outputs() {
+    let data = [0xAAu8; 4];
+
+    assert_eq!(PKCS7.pad(&data, 8).unwrap()[4..], [4, 4, 4, 4]);
+    assert_eq!(AnsiX923.pad(&data, 8).unwrap()[4..], [0, 0, 0, 4]);
+    assert_eq!(ISO7816.pad(&data, 8).unwrap()[4..], [0x80, 0, 0, 0]);
+    assert_eq!(ZeroPadding.pad(&data, 8).unwrap()[4..], [0, 0, 0, 0]);
+    let iso10126 = ISO10126.pad(&data, 8).unwrap();
+    assert_eq!((iso10126.len(), iso10126[7]), (8, 4));
+
+    // a full block of padding is added if data is already aligned
+    assert_eq!(PKCS7.pad(&[0u8; 8], 8).unwrap(), [vec![0u8; 8], vec![8u8; 8]].concat());
+    assert_eq!(ZeroPadding.pad(&[1u8; 8], 8).unwrap(), vec![1u8; 8]);
+}
+
+#[test]
+// the padding length must fit in a byte: block sizes outside [1, 255] are an error, not a panic
+fn test_pad_rejects_invalid_block_size() {
+    let schemes: [&dyn Padding; 5] = [&PKCS7, &AnsiX923, &ISO10126, &ISO7816, &ZeroPadding];
+    for scheme in schemes {
+        assert!(matches!(scheme.pad(&[1, 2, 3], 0), Err(Error::InvalidParameter(_))));
+        assert!(matches!(scheme.pad(&[1, 2, 3], 256), Err(Error::InvalidParameter(_))));
+        assert_eq!(scheme.pad(&[1, 2, 3], 255).unwrap().len(), 255);
+    }
+}
+
+#[test]
+fn test_padding_roundtrip() {
+    let schemes: [&dyn Padding; 5] = [&PKCS7, &AnsiX923, &ISO10126, &ISO7816, &ZeroPadding];
+    for scheme in schemes {
+        for len in 0..=33 {
+            let data: Vec<u8> = (1..=len as u8).collect();
+            let padded = scheme.pad(&data, 16).unwrap();
+            assert!(padded.len().is_multiple_of(16));
+            assert_eq!(scheme.unpad(&padded, 16), Ok(&data[..]));
+        }
+    }
+}
+
+#[test]
+fn test_unpad_rejects_malformed() {
+    assert_eq!(PKCS7.unpad(&[1, 2, 3], 8), Err(Error::NotBlockAligned { block_size: 8, len: 3 }));
+    assert_eq!(PKCS7.unpad(&[], 8), Err(Error::InvalidPadding));
+    assert_eq!(PKCS7.unpad(&[0, 0, 0, 0, 0, 0, 0, 0], 8), Err(Error::InvalidPadding));
+    assert_eq!(PKCS7.unpad(&[0, 0, 0, 0, 0, 0, 0, 9], 8), Err(Error::InvalidPadding));
+    assert_eq!(PKCS7.unpad(&[0, 0, 0, 0, 0, 3, 2, 3], 8), Err(Error::InvalidPadding));
+    assert_eq!(AnsiX923.unpad(&[0, 0, 0, 0, 0, 1, 0, 3], 8), Err(Error::InvalidPadding));
+    assert_eq!(ISO10126.unpad(&[0, 0, 0, 0, 0, 0, 0, 0], 8), Err(Error::InvalidPadding));
+    assert_eq!(ISO7816.unpad(&[0, 0, 0, 0, 0, 0, 0, 0], 8), Err(Error::InvalidPadding));
+    assert_eq!(ISO7816.unpad(&[0, 0, 0, 0, 0, 0x80, 1, 0], 8), Err(Error::InvalidPadding));
+}
+
+#[test]
+fn test_padded_modes() {
+    use crate::{DES, ECB, CBC};
+
+    let cipher = DES::new(0x133457799BBCDFF1);
+    let plaintext = b"not a multiple of the block size";
+
+    let ecb = Padded::new(ECB, PKCS7);
+    let ciphertext = ecb.encrypt(&cipher, &plaintext[..30]).unwrap();
+    assert_eq!(ciphertext.len(), 32);
+    assert_eq!(ecb.decrypt(&cipher, &ciphertext).unwrap(), &plaintext[..30]);
+
+    let cbc = Padded::new(CBC::<DES> { iv: 0x0123456789ABCDEF }, ISO7816);
+    let ciphertext = cbc.encrypt(&cipher, plaintext).unwrap();
+    assert_eq!(ciphertext.len(), 40);
+    assert_eq!(cbc.decrypt(&cipher, &ciphertext).unwrap(), plaintext);
+
+    // tampering with the last block breaks the padding instead of panicking
+    assert_eq!(
+        cbc.decrypt(&cipher, &ciphertext[..39]),
+        Err(Error::NotBlockAligned { block_size: 8, len: 39 })
+    );
+    let mut tampered = ciphertext.clone();
+    tampered[39] ^= 0xFF;
+    assert!(cbc.decrypt(&cipher, &tampered).is_err());
+}
@@ -1,4 +1,7 @@
+// debugging helpers, only called while working on the ciphers (and by some tests)
+
 // dumps logical bits (architecture indipendent) from MSB (63) to LSB (0)
+#[allow(dead_code)]
 pub fn logical_dump_bits_u64(val: u64) {
     for i in 0..64 {
         if i % 8 == 0 {
@@ -9,6 +12,7 @@ pub fn logical_dump_bits_u64(val: u64) {
     println!();
 }
 
+#[allow(dead_code)]
 pub fn logical_dump_bits_u32(val: u32) {
     for i in 0..32 {
         if i % 8 == 0 {
@@ -19,6 +23,7 @@ pub fn logical_dump_bits_u32(val: u32) {
     println!();
 }   
 
+#[allow(dead_code)]
 pub fn logical_dump_bits_u28(val: u32) {
     for i in 0..28 {
         if i % 8 == 0 {
@@ -30,6 +35,7 @@ pub fn logical_dump_bits_u28(val: u32) {
 }   
 
 // architecture dependend helper that dumps the memory content of an u64 (little or big endian)
+#[allow(dead_code)]
 pub fn physical_dump_bits_u64(val: u64) {
     let ptr = &val as *const u64 as *const u8;
     
@@ -41,3 +47,93 @@ pub fn physical_dump_bits_u64(val: u64) {
     }
     println!();
 }
+
+// formats bytes as a lowercase hex string
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// parses a hex string (whitespaces are ignored), None if malformed
+pub fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    Some(digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+}
+
+// compares two byte slices in constant time (for equal lengths)
+// so that the position of the first difference isn't leaked
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// input waiting for a whole block, for the hashes and MACs that process
+// their input one block at a time (es: MerkleDamgard, CBCMAC, Poly1305)
+// NOTE the owner takes it out of itself (std::mem::take) around update,
+// so that the block callback can borrow the owner mutably
+#[derive(Clone, Default)]
+pub struct BlockBuffer {
+    // bytes not yet processed (always less than a block)
+    buf: Vec<u8>,
+    size: usize,
+}
+
+impl BlockBuffer {
+    pub fn new(size: usize) -> Self {
+        Self { buf: Vec::with_capacity(size), size }
+    }
+
+    // calls f on every whole block of the buffered bytes followed by data
+    // and keeps the leftovers for later
+    pub fn update(&mut self, mut data: &[u8], mut f: impl FnMut(&[u8])) {
+        // 1. fill the pending block first
+        if !self.buf.is_empty() {
+            let missing = (self.size - self.buf.len()).min(data.len());
+            self.buf.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.buf.len() < self.size {
+                return;
+            }
+            f(&self.buf);
+            self.buf.clear();
+        }
+
+        // 2. process every full block directly from data
+        let mut blocks = data.chunks_exact(self.size);
+        for block in &mut blocks {
+            f(block);
+        }
+
+        // 3. keep the leftovers for later
+        self.buf.extend_from_slice(blocks.remainder());
+    }
+
+    // the bytes of the last incomplete block
+    pub fn pending(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+#[test]
+fn test_block_buffer() {
+    let data: Vec<u8> = (0..100).collect();
+    for chunk_size in [1, 7, 16, 33, 100] {
+        let mut buffer = BlockBuffer::new(16);
+        let mut blocks = Vec::new();
+        for chunk in data.chunks(chunk_size) {
+            buffer.update(chunk, |block| blocks.push(block.to_vec()));
+        }
+        assert_eq!(blocks.concat(), data[..96], "chunks of {}", chunk_size);
+        assert!(blocks.iter().all(|block| block.len() == 16));
+        assert_eq!(buffer.pending(), &data[96..]);
+    }
+}